@@ -1,7 +1,7 @@
 use cursive::view::Resizable;
 use cursive::views::Dialog;
 use cursive::{Cursive, CursiveExt, Vec2};
-use cursive_flexi_logger_view::{FlexiLoggerView, Indentable};
+use cursive_flexi_logger_view::{setup, FlexiLoggerView, Indentable, SetupOptions};
 
 use std::time::Duration;
 
@@ -11,17 +11,9 @@ fn main() {
     // when a new log message arrives
     let mut siv = Cursive::default();
 
-    flexi_logger::Logger::try_with_env_or_str("trace")
-        .expect("Could not create Logger from environment :(")
-        .log_to_file_and_writer(
-            flexi_logger::FileSpec::default()
-                .directory("logs")
-                .suppress_timestamp(),
-            cursive_flexi_logger_view::cursive_flexi_logger(&siv),
-        )
-        .format(flexi_logger::colored_with_thread)
-        .start()
-        .expect("failed to initialize logger!");
+    // keep the handle, dropping it shuts the logger down
+    let (_handle, _store) =
+        setup(&siv, SetupOptions::new().spec("trace")).expect("failed to initialize logger!");
 
     siv.add_layer(
         Dialog::around(FlexiLoggerView::scrollable().no_indent())
@@ -0,0 +1,98 @@
+use cursive_core::theme::{BaseColor, Color, PaletteColor, Theme};
+
+/// Which span of a log line is colored with a color derived from its content.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorBy {
+    /// Color the thread name.
+    Thread,
+    /// Color the target. The target is only shown in column mode.
+    Target,
+}
+
+/// Colors which are readable on a dark background.
+const DARK_BACKGROUND: &[Color] = &[
+    Color::Light(BaseColor::Red),
+    Color::Light(BaseColor::Green),
+    Color::Light(BaseColor::Yellow),
+    Color::Light(BaseColor::Blue),
+    Color::Light(BaseColor::Magenta),
+    Color::Light(BaseColor::Cyan),
+    Color::Dark(BaseColor::Red),
+    Color::Dark(BaseColor::Green),
+    Color::Dark(BaseColor::Yellow),
+    Color::Dark(BaseColor::Magenta),
+    Color::Dark(BaseColor::Cyan),
+];
+
+/// Colors which are readable on a light background.
+const LIGHT_BACKGROUND: &[Color] = &[
+    Color::Dark(BaseColor::Red),
+    Color::Dark(BaseColor::Green),
+    Color::Dark(BaseColor::Blue),
+    Color::Dark(BaseColor::Magenta),
+    Color::Dark(BaseColor::Cyan),
+    Color::Light(BaseColor::Red),
+    Color::Light(BaseColor::Blue),
+    Color::Light(BaseColor::Magenta),
+];
+
+/// Picks stable colors for thread names or targets.
+pub(crate) struct NameColors {
+    by: ColorBy,
+    palette: &'static [Color],
+}
+
+impl NameColors {
+    /// Create `NameColors` with a palette fitting the background of `theme`.
+    /// Without a theme, a dark background is assumed.
+    pub(crate) fn new(by: ColorBy, theme: Option<&Theme>) -> Self {
+        let light = theme.is_some_and(|theme| is_light(theme.palette[PaletteColor::View]));
+        NameColors {
+            by,
+            palette: if light {
+                LIGHT_BACKGROUND
+            } else {
+                DARK_BACKGROUND
+            },
+        }
+    }
+
+    /// The color of the thread span, if threads are colored.
+    pub(crate) fn thread(&self, thread: &str) -> Option<Color> {
+        self.color(ColorBy::Thread, thread)
+    }
+
+    /// The color of the target span, if targets are colored.
+    pub(crate) fn target(&self, target: &str) -> Option<Color> {
+        self.color(ColorBy::Target, target)
+    }
+
+    fn color(&self, by: ColorBy, name: &str) -> Option<Color> {
+        if self.by != by {
+            return None;
+        }
+        let index = fnv1a(name) % self.palette.len() as u64;
+        Some(self.palette[index as usize])
+    }
+}
+
+/// A simple hash, which is stable across runs and platforms.
+fn fnv1a(name: &str) -> u64 {
+    name.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Guess whether `color` is a light background color.
+fn is_light(color: Color) -> bool {
+    let luminance = |r: f32, g: f32, b: f32| 0.299 * r + 0.587 * g + 0.114 * b;
+    match color {
+        Color::Dark(BaseColor::White) | Color::Light(BaseColor::White) => true,
+        Color::Light(BaseColor::Yellow)
+        | Color::Light(BaseColor::Cyan)
+        | Color::Light(BaseColor::Green) => true,
+        Color::Rgb(r, g, b) => luminance(r.into(), g.into(), b.into()) > 127.0,
+        Color::RgbLowRes(r, g, b) => luminance(r.into(), g.into(), b.into()) > 2.5,
+        _ => false,
+    }
+}
@@ -0,0 +1,256 @@
+use cursive_core::theme::Effect;
+use cursive_core::utils::markup::StyledString;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::colors::NameColors;
+use crate::{level_color, LogEntry, FORMAT};
+
+/// A column of the column layout of a `FlexiLoggerView`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Column {
+    /// The time the record was created.
+    Time,
+    /// The origin of records stemming from other processes or files.
+    Source,
+    /// The name of the thread which emitted the record.
+    Thread,
+    /// The log level of the record.
+    Level,
+    /// The target of the record, usually the module path.
+    Target,
+    /// The `file:line` location the record originates from.
+    Location,
+}
+
+impl Column {
+    /// All columns in their default order.
+    pub const ALL: [Column; 6] = [
+        Column::Time,
+        Column::Source,
+        Column::Thread,
+        Column::Level,
+        Column::Target,
+        Column::Location,
+    ];
+
+    /// The label of the column shown in the header row.
+    pub fn label(self) -> &'static str {
+        match self {
+            Column::Time => "TIME",
+            Column::Source => "SOURCE",
+            Column::Thread => "THREAD",
+            Column::Level => "LEVEL",
+            Column::Target => "TARGET",
+            Column::Location => "LOCATION",
+        }
+    }
+
+    fn content(self, entry: &LogEntry) -> String {
+        match self {
+            Column::Time => entry.time.format(FORMAT).unwrap_or_default(),
+            Column::Source => entry.source.clone().unwrap_or_default(),
+            Column::Thread => entry.thread.clone(),
+            Column::Level => entry.level.to_string(),
+            Column::Target => entry.target.clone(),
+            Column::Location => match &entry.file {
+                Some(file) => format!("{}:{}", file, entry.line.unwrap_or(0)),
+                None => String::new(),
+            },
+        }
+    }
+
+    fn is_colored(self) -> bool {
+        matches!(self, Column::Time | Column::Level)
+    }
+}
+
+/// The width of a column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColumnWidth {
+    /// The column is as wide as its widest cell. Columns without any content
+    /// are omitted.
+    Auto,
+    /// The column has a fixed width, longer content is truncated.
+    Fixed(usize),
+}
+
+/// Describes which columns a `FlexiLoggerView` shows in column mode, and how
+/// wide they are.
+///
+/// # Show aligned columns with a header row
+///
+/// ```rust
+/// use cursive_flexi_logger_view::{Column, ColumnLayout, ColumnWidth, FlexiLoggerView};
+///
+/// let view = FlexiLoggerView::new().with_columns(
+///     ColumnLayout::new()
+///         .width(Column::Thread, ColumnWidth::Fixed(12))
+///         .hide(Column::Target)
+///         .header(true),
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct ColumnLayout {
+    columns: Vec<(Column, ColumnWidth, bool)>,
+    header: bool,
+}
+
+impl ColumnLayout {
+    /// Create a layout showing all columns with automatic widths and no
+    /// header row.
+    pub fn new() -> Self {
+        ColumnLayout {
+            columns: Column::ALL
+                .iter()
+                .map(|column| (*column, ColumnWidth::Auto, true))
+                .collect(),
+            header: false,
+        }
+    }
+
+    /// Set the width of `column`.
+    pub fn width(mut self, column: Column, width: ColumnWidth) -> Self {
+        self.set_width(column, width);
+        self
+    }
+
+    /// Hide `column`.
+    pub fn hide(mut self, column: Column) -> Self {
+        self.set_visible(column, false);
+        self
+    }
+
+    /// Enable or disable the header row labeling the columns.
+    pub fn header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Set the width of `column`.
+    pub fn set_width(&mut self, column: Column, width: ColumnWidth) {
+        if let Some(entry) = self.columns.iter_mut().find(|(c, _, _)| *c == column) {
+            entry.1 = width;
+        }
+    }
+
+    /// Show or hide `column`.
+    pub fn set_visible(&mut self, column: Column, visible: bool) {
+        if let Some(entry) = self.columns.iter_mut().find(|(c, _, _)| *c == column) {
+            entry.2 = visible;
+        }
+    }
+
+    /// Returns whether `column` is shown.
+    pub fn is_visible(&self, column: Column) -> bool {
+        self.columns
+            .iter()
+            .any(|(c, _, visible)| *c == column && *visible)
+    }
+
+    /// Enable or disable the header row.
+    pub fn set_header(&mut self, header: bool) {
+        self.header = header;
+    }
+
+    /// Returns whether the header row is shown.
+    pub fn has_header(&self) -> bool {
+        self.header
+    }
+
+    /// Compute the actual widths of the visible columns for `entries`.
+    pub(crate) fn widths<'a, I>(&self, entries: I) -> Vec<(Column, usize)>
+    where
+        I: Iterator<Item = &'a LogEntry> + Clone,
+    {
+        self.columns
+            .iter()
+            .filter(|(_, _, visible)| *visible)
+            .filter_map(|(column, width, _)| match width {
+                ColumnWidth::Fixed(width) => Some((*column, *width)),
+                ColumnWidth::Auto => {
+                    let width = entries
+                        .clone()
+                        .map(|entry| column.content(entry).width())
+                        .max()
+                        .unwrap_or(0);
+                    if width == 0 {
+                        None
+                    } else if self.header {
+                        Some((*column, width.max(column.label().width())))
+                    } else {
+                        Some((*column, width))
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Render the header row for the given column widths.
+    pub(crate) fn header_row(widths: &[(Column, usize)]) -> StyledString {
+        let mut line = StyledString::new();
+        for (column, width) in widths {
+            line.append_styled(fit(column.label(), *width), Effect::Bold);
+            line.append_plain(" ");
+        }
+        line.append_styled("MESSAGE", Effect::Bold);
+        line
+    }
+
+    /// Render the cells of `entry` aligned to the given column widths. The
+    /// message is appended by the caller.
+    pub(crate) fn row(
+        entry: &LogEntry,
+        widths: &[(Column, usize)],
+        colors: Option<&NameColors>,
+    ) -> StyledString {
+        let color = level_color(entry.level);
+
+        let mut line = StyledString::new();
+        for (column, width) in widths {
+            let cell = fit(&column.content(entry), *width);
+            let name_color = colors.and_then(|colors| match column {
+                Column::Thread => colors.thread(&entry.thread),
+                Column::Target => colors.target(&entry.target),
+                _ => None,
+            });
+            match name_color {
+                Some(name_color) => line.append_styled(cell, name_color),
+                None if column.is_colored() => line.append_styled(cell, color),
+                None => line.append_plain(cell),
+            }
+            line.append_plain(" ");
+        }
+        line
+    }
+}
+
+impl Default for ColumnLayout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Pad or truncate `text` to exactly `width` columns.
+fn fit(text: &str, width: usize) -> String {
+    let text_width = text.width();
+    if text_width <= width {
+        return format!("{}{}", text, " ".repeat(width - text_width));
+    }
+
+    let mut out = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w + 1 > width {
+            break;
+        }
+        out.push(c);
+        used += w;
+    }
+    if width > 0 {
+        out.push('…');
+        used += 1;
+    }
+    out.push_str(&" ".repeat(width - used));
+    out
+}
@@ -0,0 +1,424 @@
+use cursive_core::direction::Direction;
+use cursive_core::event::{AnyCb, Event, EventResult, MouseButton, MouseEvent};
+use cursive_core::theme::Effect;
+use cursive_core::view::{CannotFocus, Nameable, Selector, View, ViewNotFound};
+use cursive_core::views::Dialog;
+use cursive_core::{Cursive, Printer, Rect, Vec2};
+use unicode_width::UnicodeWidthStr;
+
+use std::sync::Arc;
+
+use crate::{
+    debug_view_action, ActivateCallback, FlexiLoggerView, LogEntry, LogFilter, LogFooterView,
+    LogSpecEditor, LogStore, LogViewAction, LogViewKeymap, FLEXI_LOGGER_DEBUG_VIEW_NAME,
+};
+
+/// A debug console with several panes, each showing the records of the same
+/// store with its own filter.
+///
+/// The panes are shown as tabs, or split the console into rows. Switch
+/// between them with `Ctrl-N` and `Ctrl-P` (see `LogViewKeymap`), or by
+/// clicking on their title. The filter and thread buttons of the console
+/// apply to the current pane.
+///
+/// # Open records in `$EDITOR` with `Enter`
+///
+/// ```rust
+/// use cursive::{Cursive, CursiveExt};
+/// use cursive_flexi_logger_view::{open_in_editor, DebugConsole};
+///
+/// fn main() {
+///     let mut siv = Cursive::default();
+///
+///     let console = DebugConsole::new().on_activate(open_in_editor);
+///     siv.add_global_callback('~', move |siv| console.toggle(siv));
+///
+///     // siv.run();
+/// }
+/// ```
+///
+/// # Show errors, network records and all records in tabs
+///
+/// ```rust
+/// use cursive::{Cursive, CursiveExt};
+/// use cursive_flexi_logger_view::{DebugConsole, LogFilter};
+/// use flexi_logger::Level;
+///
+/// fn main() {
+///     let mut siv = Cursive::default();
+///
+///     let console = DebugConsole::new()
+///         .pane("Errors", LogFilter::new().with_level(Level::Error))
+///         .pane("Network", LogFilter::new().with_target("hyper"))
+///         .pane("All", LogFilter::new());
+///     siv.add_global_callback('~', move |siv| console.toggle(siv));
+///
+///     // siv.run();
+/// }
+/// ```
+#[derive(Clone)]
+pub struct DebugConsole {
+    store: LogStore,
+    panes: Vec<(String, LogFilter)>,
+    split: bool,
+    keymap: LogViewKeymap,
+    interactive: bool,
+    on_activate: Option<ActivateCallback>,
+    spec_editor: Option<LogSpecEditor>,
+}
+
+impl DebugConsole {
+    /// Create a console for the global `LogStore`. Without any pane added, it
+    /// shows all records in a single pane.
+    pub fn new() -> Self {
+        DebugConsole {
+            store: LogStore::global(),
+            panes: Vec::new(),
+            split: false,
+            keymap: LogViewKeymap::new(),
+            interactive: true,
+            on_activate: None,
+            spec_editor: None,
+        }
+    }
+
+    /// Create the console of `show_flexi_logger_debug_console`, whose pane
+    /// leaves all keys to the application and its `ScrollView`.
+    pub(crate) fn read_only() -> Self {
+        DebugConsole {
+            interactive: false,
+            ..Self::new()
+        }
+    }
+
+    /// Show the records of `store` instead of the ones of the global store.
+    pub fn store(mut self, store: LogStore) -> Self {
+        self.store = store;
+        self
+    }
+
+    /// Add a pane titled `title`, which shows the records passing `filter`.
+    pub fn pane<S: Into<String>>(mut self, title: S, filter: LogFilter) -> Self {
+        self.panes.push((title.into(), filter));
+        self
+    }
+
+    /// Show all panes at once, stacked in rows, instead of as tabs.
+    pub fn split(mut self, split: bool) -> Self {
+        self.split = split;
+        self
+    }
+
+    /// Handle the events of the panes as bound in `keymap`.
+    pub fn keymap(mut self, keymap: LogViewKeymap) -> Self {
+        self.keymap = keymap;
+        self
+    }
+
+    /// Call `cb` when the user activates a record of a pane, e.g.
+    /// `open_in_editor`. See `FlexiLoggerView::on_activate`.
+    pub fn on_activate<F>(mut self, cb: F) -> Self
+    where
+        F: Fn(&mut Cursive, &LogEntry) + Send + Sync + 'static,
+    {
+        self.on_activate = Some(Arc::new(cb));
+        self
+    }
+
+    /// Change the log specification with `editor`, from a button of the
+    /// console or the spec actions of the keymap.
+    pub fn spec_editor(mut self, editor: LogSpecEditor) -> Self {
+        self.spec_editor = Some(editor);
+        self
+    }
+
+    /// Show the console.
+    pub fn show(&self, siv: &mut Cursive) {
+        let mut panes = self.panes.clone();
+        if panes.is_empty() {
+            panes.push(("All".to_string(), LogFilter::new()));
+        }
+        let panes = panes
+            .into_iter()
+            .map(|(title, filter)| {
+                let mut view = FlexiLoggerView::with_store(self.store.clone()).with_filter(filter);
+                if self.interactive {
+                    view = view.with_keymap(self.keymap.clone()).with_bookmarks();
+                    if let Some(cb) = self.on_activate.clone() {
+                        view.set_on_activate(move |siv, entry| cb(siv, entry));
+                    }
+                } else {
+                    view.set_interactive(false);
+                }
+                (title, LogFooterView::new(view.into_scrollable()))
+            })
+            .collect();
+
+        let mut dialog = Dialog::around(
+            ConsolePanes {
+                panes,
+                active: 0,
+                split: self.split,
+                last_size: Vec2::zero(),
+                spec_editor: self.spec_editor.clone(),
+            }
+            .with_name(FLEXI_LOGGER_DEBUG_VIEW_NAME),
+        )
+        .title("Debug console")
+        .button("Filter", |siv| {
+            debug_view_action(siv, LogViewAction::Search)
+        })
+        .button("Threads", |siv| {
+            debug_view_action(siv, LogViewAction::Filter)
+        });
+        if let Some(editor) = self.spec_editor.clone() {
+            dialog.add_button("Spec", move |siv| editor.show(siv));
+        }
+        siv.add_layer(dialog);
+    }
+
+    /// Show the console, or hide it if a console is already shown.
+    pub fn toggle(&self, siv: &mut Cursive) {
+        if let Some(pos) = siv
+            .screen_mut()
+            .find_layer_from_name(FLEXI_LOGGER_DEBUG_VIEW_NAME)
+        {
+            siv.screen_mut().remove_layer(pos);
+        } else {
+            self.show(siv);
+        }
+    }
+}
+
+impl Default for DebugConsole {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The panes of a `DebugConsole`, of which one receives the events.
+pub(crate) struct ConsolePanes {
+    panes: Vec<(String, LogFooterView)>,
+    active: usize,
+    split: bool,
+    last_size: Vec2,
+    spec_editor: Option<LogSpecEditor>,
+}
+
+impl ConsolePanes {
+    /// Returns the log view of the current pane.
+    pub(crate) fn active_view_mut(&mut self) -> &mut FlexiLoggerView {
+        self.panes[self.active].1.get_inner_mut().get_inner_mut()
+    }
+
+    fn has_tab_bar(&self) -> bool {
+        !self.split && self.panes.len() > 1
+    }
+
+    /// Returns the row of the title and the area of the content of each
+    /// shown pane.
+    fn areas(&self, size: Vec2) -> Vec<(usize, Option<usize>, Rect)> {
+        if self.split {
+            let count = self.panes.len();
+            let height = size.y / count;
+            return (0..count)
+                .map(|index| {
+                    let top = index * height;
+                    let rows = if index + 1 == count {
+                        size.y - top
+                    } else {
+                        height
+                    };
+                    let content = Rect::from_size((0, top + 1), (size.x, rows.saturating_sub(1)));
+                    (index, Some(top), content)
+                })
+                .collect();
+        }
+        let header = usize::from(self.has_tab_bar());
+        let content = Rect::from_size((0, header), size.saturating_sub((0, header)));
+        vec![(self.active, None, content)]
+    }
+
+    /// Returns the column ranges of the titles in the tab bar.
+    fn tabs(&self) -> Vec<(usize, usize)> {
+        let mut x = 0;
+        self.panes
+            .iter()
+            .map(|(title, _)| {
+                let start = x;
+                x += title.width() + 3;
+                (start, start + title.width() + 2)
+            })
+            .collect()
+    }
+
+    fn switch(&mut self, forward: bool) -> EventResult {
+        let count = self.panes.len();
+        self.active = if forward {
+            (self.active + 1) % count
+        } else {
+            (self.active + count - 1) % count
+        };
+        EventResult::Consumed(None)
+    }
+
+    /// Switch to the pane whose title or content was clicked at `position`.
+    fn click(&mut self, position: Vec2, size: Vec2) -> Option<EventResult> {
+        if self.has_tab_bar() && position.y == 0 {
+            let index = self
+                .tabs()
+                .iter()
+                .position(|(start, end)| (*start..*end).contains(&position.x))?;
+            self.active = index;
+            return Some(EventResult::Consumed(None));
+        }
+        let (index, title, _) = self
+            .areas(size)
+            .into_iter()
+            .find(|(_, title, area)| *title == Some(position.y) || area.contains(position))?;
+        self.active = index;
+        // clicks into the content are handled by the pane
+        if title == Some(position.y) {
+            Some(EventResult::Consumed(None))
+        } else {
+            None
+        }
+    }
+}
+
+impl View for ConsolePanes {
+    fn draw(&self, printer: &Printer<'_, '_>) {
+        if self.has_tab_bar() {
+            for (index, (start, _)) in self.tabs().into_iter().enumerate() {
+                if start > 0 {
+                    printer.print((start - 1, 0), "│");
+                }
+                let label = format!(" {} ", self.panes[index].0);
+                if index == self.active {
+                    printer.with_effect(Effect::Reverse, |printer| {
+                        printer.print((start, 0), &label);
+                    });
+                } else {
+                    printer.print((start, 0), &label);
+                }
+            }
+        }
+
+        for (index, title, area) in self.areas(printer.size) {
+            let active = index == self.active;
+            if let Some(y) = title {
+                printer.print_hline((0, y), printer.size.x, "─");
+                let label = format!(" {} ", self.panes[index].0);
+                let effect = if active { Effect::Reverse } else { Effect::Dim };
+                printer.with_effect(effect, |printer| printer.print((1, y), &label));
+            }
+            let printer = printer
+                .offset(area.top_left())
+                .cropped(area.size())
+                .focused(printer.focused && active);
+            self.panes[index].1.draw(&printer);
+        }
+    }
+
+    fn layout(&mut self, size: Vec2) {
+        self.last_size = size;
+        for (index, _, area) in self.areas(size) {
+            self.panes[index].1.layout(area.size());
+        }
+    }
+
+    fn required_size(&mut self, constraint: Vec2) -> Vec2 {
+        let areas = self.areas(constraint);
+        let mut size = Vec2::zero();
+        for (index, _, area) in &areas {
+            let required = self.panes[*index].1.required_size(area.size());
+            size.x = size.x.max(required.x);
+            size.y += required.y + usize::from(self.split);
+        }
+        size.y += usize::from(self.has_tab_bar());
+        if self.has_tab_bar() {
+            let tabs = self.tabs().last().map_or(0, |(_, end)| *end);
+            size.x = size.x.max(tabs);
+        }
+        size
+    }
+
+    fn on_event(&mut self, event: Event) -> EventResult {
+        let view = self.active_view_mut();
+        let action = view
+            .keymap()
+            .action(&event)
+            .filter(|_| view.is_interactive());
+        match action {
+            Some(LogViewAction::NextPane) => return self.switch(true),
+            Some(LogViewAction::PreviousPane) => return self.switch(false),
+            Some(LogViewAction::EditSpec) => {
+                if let Some(editor) = self.spec_editor.clone() {
+                    return EventResult::with_cb(move |siv| editor.show(siv));
+                }
+            }
+            Some(LogViewAction::RestoreSpec) => {
+                if let Some(editor) = &self.spec_editor {
+                    editor.restore();
+                    return EventResult::Consumed(None);
+                }
+            }
+            _ => {}
+        }
+
+        if let Event::Mouse {
+            offset,
+            position,
+            event: MouseEvent::Press(MouseButton::Left),
+        } = event
+        {
+            if let Some(position) = position.checked_sub(offset) {
+                if let Some(result) = self.click(position, self.last_size) {
+                    return result;
+                }
+            }
+        }
+
+        let area = self
+            .areas(self.last_size)
+            .into_iter()
+            .find(|(index, _, _)| *index == self.active)
+            .map(|(_, _, area)| area);
+        match area {
+            Some(area) => self.panes[self.active]
+                .1
+                .on_event(event.relativized(area.top_left())),
+            None => EventResult::Ignored,
+        }
+    }
+
+    fn take_focus(&mut self, source: Direction) -> Result<EventResult, CannotFocus> {
+        self.panes[self.active].1.take_focus(source)
+    }
+
+    fn important_area(&self, size: Vec2) -> Rect {
+        self.areas(size)
+            .into_iter()
+            .find(|(index, _, _)| *index == self.active)
+            .map(|(index, _, area)| {
+                self.panes[index].1.important_area(area.size()) + area.top_left()
+            })
+            .unwrap_or_else(|| Rect::from_size((0, 0), size))
+    }
+
+    fn call_on_any(&mut self, selector: &Selector, cb: AnyCb) {
+        for (_, pane) in &mut self.panes {
+            pane.call_on_any(selector, cb);
+        }
+    }
+
+    fn focus_view(&mut self, selector: &Selector) -> Result<EventResult, ViewNotFound> {
+        for (index, (_, pane)) in self.panes.iter_mut().enumerate() {
+            if let Ok(result) = pane.focus_view(selector) {
+                self.active = index;
+                return Ok(result);
+            }
+        }
+        Err(ViewNotFound)
+    }
+}
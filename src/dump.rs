@@ -0,0 +1,189 @@
+use cursive_core::theme::{BaseColor, Color, ColorType, Effect, EffectStatus};
+use cursive_core::utils::markup::StyledString;
+
+use flexi_logger::Level;
+
+use std::io::{self, IsTerminal, Write};
+
+use crate::{append_fields, level_color, styled_entry, LogEntry, LogStore};
+
+/// Writes the records retained in a `LogStore` as text, e.g. to keep them
+/// visible after the cursive application exits.
+///
+/// # Print the warnings and errors when the application exits
+///
+/// ```rust
+/// use cursive::{Cursive, CursiveExt};
+/// use cursive_flexi_logger_view::LogDump;
+/// use flexi_logger::Level;
+///
+/// fn main() {
+///     let mut siv = Cursive::default();
+///
+///     // dropped after `siv.run()` returned and the terminal was released
+///     let _dump = LogDump::new().level(Level::Warn).guard();
+///
+///     // siv.run();
+/// }
+/// ```
+#[derive(Clone)]
+pub struct LogDump {
+    store: LogStore,
+    level: Level,
+    ansi: Option<bool>,
+}
+
+impl LogDump {
+    /// Create a dump of all records in the global `LogStore`.
+    pub fn new() -> Self {
+        LogDump {
+            store: LogStore::global(),
+            level: Level::Trace,
+            ansi: None,
+        }
+    }
+
+    /// Dump the records of `store` instead of the ones of the global store.
+    pub fn store(mut self, store: LogStore) -> Self {
+        self.store = store;
+        self
+    }
+
+    /// Only dump records which are at least as severe as `level`.
+    pub fn level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Color the records with ANSI escape codes, or print them as plain text.
+    /// By default, records printed to a terminal are colored.
+    pub fn ansi(mut self, ansi: bool) -> Self {
+        self.ansi = Some(ansi);
+        self
+    }
+
+    /// Write the records to `out`. Unless set otherwise, they are written as
+    /// plain text. The logger is flushed first, so records still queued by a
+    /// non-blocking `CursiveLogWriter` are included.
+    pub fn write_to<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        log::logger().flush();
+        let logs = self.store.lock();
+        let entries = logs.iter().filter(|entry| entry.level <= self.level);
+        write_entries(out, entries, self.ansi.unwrap_or(false), "\n")
+    }
+
+    /// Write the records to stderr, after flushing the logger like
+    /// `write_to`.
+    pub fn print(&self) -> io::Result<()> {
+        let stderr = io::stderr();
+        let ansi = self.ansi.unwrap_or_else(|| stderr.is_terminal());
+        log::logger().flush();
+        // this may run while unwinding, don't wait for a lock held by a
+        // panicked thread forever
+        let logs = self
+            .store
+            .try_lock()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::WouldBlock, "log store is locked"))?;
+        let entries = logs.iter().filter(|entry| entry.level <= self.level);
+        write_entries(&mut stderr.lock(), entries, ansi, "\n")
+    }
+
+    /// Returns a guard which writes the records to stderr when it is dropped.
+    pub fn guard(self) -> LogDumpGuard {
+        LogDumpGuard { dump: self }
+    }
+}
+
+impl Default for LogDump {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Writes the records of a `LogDump` to stderr when dropped.
+///
+/// Create the guard before running cursive, so it is dropped after cursive
+/// released the terminal, even if the application panics.
+pub struct LogDumpGuard {
+    dump: LogDump,
+}
+
+impl Drop for LogDumpGuard {
+    fn drop(&mut self) {
+        let _ = self.dump.print();
+    }
+}
+
+/// Write `entries` as text lines, colored with ANSI escape codes if `ansi` is
+/// set. `newline` separates lines, terminals in raw mode need `"\r\n"`.
+pub(crate) fn write_entries<'a, W, I>(
+    out: &mut W,
+    entries: I,
+    ansi: bool,
+    newline: &str,
+) -> io::Result<()>
+where
+    W: Write + ?Sized,
+    I: IntoIterator<Item = &'a LogEntry>,
+{
+    for entry in entries {
+        let mut line = styled_entry(entry, None);
+        line.append_styled(&entry.message, level_color(entry.level));
+        append_fields(&mut line, entry);
+        let text = if ansi {
+            to_ansi(&line)
+        } else {
+            line.source().to_string()
+        };
+        write!(out, "{}{}", text.replace('\n', newline), newline)?;
+    }
+    out.flush()
+}
+
+/// Convert `line` into a string with ANSI escape codes for its colors and
+/// effects.
+fn to_ansi(line: &StyledString) -> String {
+    let mut out = String::new();
+    for span in line.spans() {
+        let mut codes = Vec::new();
+        for (effect, code) in &[
+            (Effect::Bold, "1"),
+            (Effect::Dim, "2"),
+            (Effect::Italic, "3"),
+            (Effect::Underline, "4"),
+        ] {
+            if span.attr.effects.statuses[*effect] == EffectStatus::On {
+                codes.push(code.to_string());
+            }
+        }
+        if let ColorType::Color(color) = span.attr.color.front {
+            codes.extend(color_code(color));
+        }
+        if codes.is_empty() {
+            out.push_str(span.content);
+        } else {
+            out.push_str(&format!("\x1b[{}m{}\x1b[0m", codes.join(";"), span.content));
+        }
+    }
+    out
+}
+
+fn color_code(color: Color) -> Option<String> {
+    let base = |base: BaseColor| match base {
+        BaseColor::Black => 0,
+        BaseColor::Red => 1,
+        BaseColor::Green => 2,
+        BaseColor::Yellow => 3,
+        BaseColor::Blue => 4,
+        BaseColor::Magenta => 5,
+        BaseColor::Cyan => 6,
+        BaseColor::White => 7,
+    };
+    match color {
+        Color::Dark(color) => Some((30 + base(color)).to_string()),
+        Color::Light(color) => Some((90 + base(color)).to_string()),
+        Color::Rgb(r, g, b) => Some(format!("38;2;{};{};{}", r, g, b)),
+        Color::RgbLowRes(r, g, b) => Some(format!("38;5;{}", 16 + 36 * r + 6 * g + b)),
+        Color::TerminalDefault => None,
+    }
+}
@@ -0,0 +1,75 @@
+use cursive_core::Cursive;
+
+use std::io::Write;
+use std::process::Command;
+
+use crate::terminal::{DISABLE_MOUSE, ENABLE_MOUSE, ENTER_SCREEN, LEAVE_SCREEN};
+use crate::LogEntry;
+
+/// Open the source location of `entry` in the user's editor.
+///
+/// The editor is taken from `$VISUAL` or `$EDITOR` and falls back to `vi`. It
+/// is invoked as `$EDITOR +line file` on the terminal of cursive. When the
+/// editor exits, the UI is redrawn. Entries without a source file are ignored.
+///
+/// This is meant to be used as the activation callback of a `FlexiLoggerView`.
+///
+/// # Supported backends
+///
+/// Cursive's backend is not suspended while the editor runs. The callback
+/// only leaves the alternate screen and stops mouse reporting by writing
+/// escape sequences to stdout, and enters the screen and restarts the mouse
+/// reporting of the crossterm backend afterwards. The terminal stays in raw
+/// mode, which full-screen editors like `vim`, `nano` or `emacs -nw` don't
+/// mind, as they set up the terminal themselves.
+///
+/// This works with the crossterm backend. The ncurses and pancurses backends
+/// are not told about the editor and may lose their keypad mode to it, and
+/// the termion backend leaves stdin non-blocking, which editors fail to read
+/// from.
+///
+/// # Open the location of a record on activation
+///
+/// ```rust
+/// use cursive_flexi_logger_view::{open_in_editor, FlexiLoggerView};
+///
+/// let view = FlexiLoggerView::new().on_activate(open_in_editor);
+/// ```
+pub fn open_in_editor(siv: &mut Cursive, entry: &LogEntry) {
+    let file = match &entry.file {
+        Some(file) => file,
+        None => return,
+    };
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+
+    // the editor variable may contain arguments, e.g. `code --wait`
+    let mut words = editor.split_whitespace();
+    let program = match words.next() {
+        Some(program) => program,
+        None => return,
+    };
+
+    let mut stdout = std::io::stdout();
+    // stop mouse reporting, or the editor receives every mouse event as
+    // escape sequences
+    let _ = write!(stdout, "{}{}", DISABLE_MOUSE, LEAVE_SCREEN).and_then(|_| stdout.flush());
+    let status = Command::new(program)
+        .args(words)
+        .arg(format!("+{}", entry.line.unwrap_or(1)))
+        .arg(file)
+        .status();
+    let _ = write!(stdout, "{}{}", ENTER_SCREEN, ENABLE_MOUSE).and_then(|_| stdout.flush());
+
+    // the editor left the terminal in an unknown state, redraw everything
+    siv.clear();
+
+    match status {
+        Ok(status) if !status.success() => {
+            log::warn!("editor `{}` exited with {}", editor, status)
+        }
+        Err(err) => log::error!("failed to run editor `{}`: {}", editor, err),
+        Ok(_) => {}
+    }
+}
@@ -0,0 +1,177 @@
+use flexi_logger::Level;
+
+use std::collections::BTreeSet;
+
+use crate::LogEntry;
+
+/// Decides which log entries a `FlexiLoggerView` shows.
+///
+/// The default filter shows all entries.
+///
+/// # Hide the records of a thread
+///
+/// ```rust
+/// use cursive_flexi_logger_view::{FlexiLoggerView, LogFilter};
+///
+/// let view = FlexiLoggerView::new().with_filter(LogFilter::new().hide_thread("worker"));
+/// ```
+///
+/// # Show the records of a single request
+///
+/// ```rust
+/// use cursive_flexi_logger_view::{FlexiLoggerView, LogFilter};
+///
+/// let view = FlexiLoggerView::new().with_filter(LogFilter::new().with_query("request_id=abc"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct LogFilter {
+    level: Option<Level>,
+    target: Option<String>,
+    hidden_threads: BTreeSet<String>,
+    query: String,
+    terms: Vec<Term>,
+}
+
+/// A single whitespace separated term of a filter query.
+#[derive(Clone, Debug)]
+enum Term {
+    /// `key=value` matches entries with the given key-value pair, `key=`
+    /// matches entries having the key at all.
+    Field(String, String),
+    /// Any other word matches entries whose message contains it, ignoring case.
+    Text(String),
+}
+
+impl Term {
+    fn parse(word: &str) -> Self {
+        match word.split_once('=') {
+            Some((key, value)) if !key.is_empty() => {
+                Term::Field(key.to_string(), value.to_string())
+            }
+            _ => Term::Text(word.to_lowercase()),
+        }
+    }
+
+    fn matches(&self, entry: &LogEntry) -> bool {
+        match self {
+            Term::Field(key, value) => entry
+                .field(key)
+                .is_some_and(|field| value.is_empty() || field == value),
+            Term::Text(text) => entry.message.to_lowercase().contains(text.as_str()),
+        }
+    }
+}
+
+impl LogFilter {
+    /// Create a new filter, which shows all entries.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only show entries which are at least as severe as `level`.
+    pub fn with_level(mut self, level: Level) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Only show entries which are at least as severe as `level`, or entries
+    /// of all levels by passing `None`.
+    pub fn set_level(&mut self, level: Option<Level>) {
+        self.level = level;
+    }
+
+    /// Returns the least severe level which is shown, if any.
+    pub fn level(&self) -> Option<Level> {
+        self.level
+    }
+
+    /// Only show entries of `target` and its submodules, e.g. `hyper` also
+    /// shows `hyper::proto`.
+    pub fn with_target<S: Into<String>>(mut self, target: S) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    /// Only show entries of `target` and its submodules, or entries of all
+    /// targets by passing `None`.
+    pub fn set_target(&mut self, target: Option<String>) {
+        self.target = target;
+    }
+
+    /// Returns the target whose entries are shown, if any.
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
+
+    /// Hide the entries of the thread named `thread`.
+    pub fn hide_thread<S: Into<String>>(mut self, thread: S) -> Self {
+        self.set_thread_visible(thread, false);
+        self
+    }
+
+    /// Show or hide the entries of the thread named `thread`.
+    pub fn set_thread_visible<S: Into<String>>(&mut self, thread: S, visible: bool) {
+        let thread = thread.into();
+        if visible {
+            self.hidden_threads.remove(&thread);
+        } else {
+            self.hidden_threads.insert(thread);
+        }
+    }
+
+    /// Returns whether the entries of the thread named `thread` are shown.
+    pub fn is_thread_visible(&self, thread: &str) -> bool {
+        !self.hidden_threads.contains(thread)
+    }
+
+    /// Returns the names of the hidden threads in alphabetical order.
+    pub fn hidden_threads(&self) -> impl Iterator<Item = &str> {
+        self.hidden_threads.iter().map(String::as_str)
+    }
+
+    /// Only show the entries matching `query`.
+    ///
+    /// See `set_query` for the syntax.
+    pub fn with_query<S: Into<String>>(mut self, query: S) -> Self {
+        self.set_query(query);
+        self
+    }
+
+    /// Only show the entries matching `query`, which consists of whitespace
+    /// separated terms. An entry has to match all terms:
+    ///
+    ///  - `key=value` matches entries with the given key-value pair,
+    ///  - `key=` matches entries having a value for `key`,
+    ///  - any other word matches entries whose message contains it, ignoring
+    ///    case.
+    ///
+    /// An empty query matches all entries.
+    pub fn set_query<S: Into<String>>(&mut self, query: S) {
+        self.query = query.into();
+        self.terms = self.query.split_whitespace().map(Term::parse).collect();
+    }
+
+    /// Returns the current query.
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Returns whether `entry` passes the filter.
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        self.level.is_none_or(|level| entry.level <= level)
+            && self
+                .target
+                .as_ref()
+                .is_none_or(|target| is_in_module(&entry.target, target))
+            && self.is_thread_visible(&entry.thread)
+            && self.terms.iter().all(|term| term.matches(entry))
+    }
+}
+
+/// Returns whether `target` is the module `module` or one of its submodules.
+pub(crate) fn is_in_module(target: &str, module: &str) -> bool {
+    match target.strip_prefix(module) {
+        Some(rest) => rest.is_empty() || rest.starts_with("::"),
+        None => false,
+    }
+}
@@ -0,0 +1,595 @@
+use cursive_core::{CbSink, Cursive};
+use flexi_logger::{DeferredNow, Level};
+use time::{format_description::FormatItem, macros::format_description, OffsetDateTime};
+
+use std::fs::{File, Metadata};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::{LogEntry, LogStore};
+
+/// The timestamp format used by the predefined `flexi_logger` formats.
+const FILE_TS_FORMAT: &[FormatItem<'static>] = format_description!(
+    "[year]-[month]-[day] [hour]:[minute]:[second].[subsecond] \
+        [offset_hour sign:mandatory]:[offset_minute]"
+);
+
+/// The infix `flexi_logger` uses for the file currently written to when
+/// rotation is enabled.
+const CURRENT_INFIX: &str = "_rCURRENT";
+
+/// Follows a log file like `tail -F` and feeds its lines into a `LogStore`.
+///
+/// Lines written in one of the predefined `flexi_logger` formats (with or
+/// without colors) are parsed into their level, timestamp, thread, module and
+/// source location. Lines which cannot be parsed are treated as continuation
+/// lines of the previous record, so multi-line messages stay together.
+///
+/// The follower copes with truncated and rotated files. If the followed path
+/// does not exist, but a file with `flexi_logger`'s `_rCURRENT` infix does
+/// (e.g. `logs/daemon_rCURRENT.log` for `logs/daemon.log`), that file is
+/// followed instead.
+///
+/// # Follow the log file of a helper daemon
+///
+/// ```rust
+/// use cursive::{Cursive, CursiveExt};
+/// use cursive_flexi_logger_view::{FileFollower, FlexiLoggerView};
+///
+/// fn main() {
+///     let mut siv = Cursive::default();
+///
+///     let _follower = FileFollower::new("logs/daemon.log")
+///         .source("daemon")
+///         .spawn(&siv)
+///         .expect("failed to follow log file");
+///
+///     siv.add_layer(FlexiLoggerView::scrollable());
+///     // siv.run();
+/// }
+/// ```
+pub struct FileFollower {
+    path: PathBuf,
+    source: Option<String>,
+    store: LogStore,
+    poll_interval: Duration,
+    from_beginning: bool,
+}
+
+impl FileFollower {
+    /// Create a new `FileFollower` for the file at `path`, which feeds the
+    /// global `LogStore`.
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        FileFollower {
+            path: path.into(),
+            source: None,
+            store: LogStore::global(),
+            poll_interval: Duration::from_millis(250),
+            from_beginning: false,
+        }
+    }
+
+    /// Set the source name the followed records are tagged with. Defaults to
+    /// the file name of the followed path.
+    pub fn source<S: Into<String>>(mut self, source: S) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Feed the followed records into `store` instead of the global `LogStore`.
+    pub fn store(mut self, store: LogStore) -> Self {
+        self.store = store;
+        self
+    }
+
+    /// Set how often the followed file is checked for new content.
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Read the content already present in the file, instead of starting at
+    /// its end.
+    pub fn from_beginning(mut self) -> Self {
+        self.from_beginning = true;
+        self
+    }
+
+    /// Start following the file on a background thread. Cursive is asked to
+    /// redraw through its callback sink whenever new records arrive.
+    ///
+    /// The follower stops when the returned handle is dropped or when the
+    /// cursive callback sink is closed.
+    pub fn spawn(self, siv: &Cursive) -> io::Result<FileFollowerHandle> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let source = self.source.clone().unwrap_or_else(|| {
+            self.path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| self.path.display().to_string())
+        });
+        let mut state = FollowState {
+            follower: self,
+            source,
+            sink: siv.cb_sink().clone(),
+            file: None,
+            partial: Vec::new(),
+            pending: None,
+        };
+        let thread = thread::Builder::new()
+            .name("flexi-log-follower".to_string())
+            .spawn({
+                let stop = Arc::clone(&stop);
+                move || state.run(&stop)
+            })?;
+
+        Ok(FileFollowerHandle {
+            stop,
+            thread: Some(thread),
+        })
+    }
+}
+
+/// A handle to a running `FileFollower`. Dropping it stops the follower.
+pub struct FileFollowerHandle {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl FileFollowerHandle {
+    /// Stop following the file and wait for the background thread to finish.
+    pub fn stop(self) {
+        // the actual work is done in `Drop`
+    }
+}
+
+impl Drop for FileFollowerHandle {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+struct OpenFile {
+    file: File,
+    id: Option<FileId>,
+    offset: u64,
+}
+
+struct FollowState {
+    follower: FileFollower,
+    source: String,
+    sink: CbSink,
+    file: Option<OpenFile>,
+    partial: Vec<u8>,
+    pending: Option<LogEntry>,
+}
+
+impl FollowState {
+    fn run(&mut self, stop: &AtomicBool) {
+        let mut first = true;
+        while !stop.load(Ordering::Relaxed) {
+            let mut entries = Vec::new();
+            self.poll(&mut entries, first);
+            first = false;
+
+            if !entries.is_empty() {
+                self.follower.store.extend(entries);
+                if self.sink.send(Box::new(|_siv| {})).is_err() {
+                    // cursive is gone, nobody is left to show the records
+                    break;
+                }
+            }
+            thread::sleep(self.follower.poll_interval);
+        }
+    }
+
+    fn poll(&mut self, entries: &mut Vec<LogEntry>, first: bool) {
+        let path = resolve_path(&self.follower.path);
+        let meta = path.as_ref().and_then(|path| path.metadata().ok());
+
+        if let (Some(open), Some(meta)) = (&mut self.file, meta) {
+            let id = file_id(&meta);
+            if id.is_some() && id != open.id {
+                // the file got rotated, read the rest of the old one before
+                // switching over to the new file
+                self.read_to_end(entries);
+                self.flush_partial(entries);
+                self.file = None;
+            } else if meta.len() < open.offset {
+                // the file got truncated, start over
+                open.offset = 0;
+                self.partial.clear();
+            }
+        }
+        // if the file has been moved away and not yet been recreated, keep
+        // reading whatever the old file still gets
+
+        if self.file.is_none() {
+            if let Some(path) = path {
+                if let Ok(file) = File::open(path) {
+                    if let Ok(meta) = file.metadata() {
+                        let offset = if first && !self.follower.from_beginning {
+                            meta.len()
+                        } else {
+                            0
+                        };
+                        self.file = Some(OpenFile {
+                            file,
+                            id: file_id(&meta),
+                            offset,
+                        });
+                    }
+                }
+            }
+        }
+
+        self.read_to_end(entries);
+        // records are written at once, so an incomplete record is not
+        // expected to be continued in the next poll
+        entries.extend(self.pending.take());
+    }
+
+    fn read_to_end(&mut self, entries: &mut Vec<LogEntry>) {
+        let open = match &mut self.file {
+            Some(open) => open,
+            None => return,
+        };
+
+        let mut buf = Vec::new();
+        let read = open
+            .file
+            .seek(SeekFrom::Start(open.offset))
+            .and_then(|_| open.file.read_to_end(&mut buf));
+        if read.is_err() {
+            return;
+        }
+        open.offset += buf.len() as u64;
+        self.partial.extend_from_slice(&buf);
+
+        while let Some(pos) = self.partial.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.partial.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line);
+            self.push_line(line.trim_end_matches(&['\r', '\n'][..]), entries);
+        }
+    }
+
+    fn flush_partial(&mut self, entries: &mut Vec<LogEntry>) {
+        if !self.partial.is_empty() {
+            let line = String::from_utf8_lossy(&self.partial).into_owned();
+            self.partial.clear();
+            self.push_line(&line, entries);
+        }
+    }
+
+    fn push_line(&mut self, line: &str, entries: &mut Vec<LogEntry>) {
+        let line = strip_ansi(line);
+        match parse_line(&line) {
+            Some(mut entry) => {
+                entry.source = Some(self.source.clone());
+                entries.extend(self.pending.replace(entry));
+            }
+            None => match &mut self.pending {
+                Some(pending) => {
+                    pending.message.push('\n');
+                    pending.message.push_str(&line);
+                }
+                None => {
+                    self.pending = Some(LogEntry {
+                        seq: 0,
+                        time: *DeferredNow::new().now(),
+                        level: Level::Info,
+                        thread: "(unnamed)".to_string(),
+                        target: String::new(),
+                        file: None,
+                        line: None,
+                        message: line,
+                        source: Some(self.source.clone()),
+                        fields: Vec::new(),
+                        json_start: None,
+                    });
+                }
+            },
+        }
+    }
+}
+
+/// Returns the path which is actually written to, taking `flexi_logger`'s
+/// rotation naming into account.
+fn resolve_path(path: &Path) -> Option<PathBuf> {
+    if path.exists() {
+        return Some(path.to_path_buf());
+    }
+
+    let stem = path.file_stem()?.to_string_lossy();
+    let name = match path.extension() {
+        Some(ext) => format!("{}{}.{}", stem, CURRENT_INFIX, ext.to_string_lossy()),
+        None => format!("{}{}", stem, CURRENT_INFIX),
+    };
+    let current = path.with_file_name(name);
+    if current.exists() {
+        Some(current)
+    } else {
+        None
+    }
+}
+
+type FileId = (u64, u64);
+
+#[cfg(unix)]
+fn file_id(meta: &Metadata) -> Option<FileId> {
+    use std::os::unix::fs::MetadataExt;
+    Some((meta.dev(), meta.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_id(meta: &Metadata) -> Option<FileId> {
+    // there is no inode on this platform, the creation time of the file is
+    // the next best thing to detect a recreated file
+    let created = meta.created().ok()?;
+    let since_epoch = created.duration_since(std::time::UNIX_EPOCH).ok()?;
+    Some((since_epoch.as_secs(), since_epoch.subsec_nanos() as u64))
+}
+
+/// Remove ANSI escape sequences, as written by the colored `flexi_logger` formats.
+fn strip_ansi(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            if chars.next() == Some('[') {
+                // skip parameters until the final byte of the sequence
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Parse a line written in one of the predefined `flexi_logger` formats, e.g.
+///
+/// ```text
+/// INFO [my_prog::module] message
+/// [2016-01-13 15:25:01.640870 +01:00] INFO [src/foo/bar.rs:26] message
+/// [2016-01-13 15:25:01.640870 +01:00] INFO [foo::bar] src/foo/bar.rs:26: message
+/// [2016-01-13 15:25:01.640870 +01:00] T["worker"] INFO [src/foo/bar.rs:26] message
+/// ```
+///
+/// Returns `None` if the line does not start a new record.
+fn parse_line(line: &str) -> Option<LogEntry> {
+    let mut rest = line;
+
+    let mut time = None;
+    if let Some(stripped) = rest.strip_prefix('[') {
+        let end = stripped.find(']')?;
+        time = Some(OffsetDateTime::parse(&stripped[..end], &FILE_TS_FORMAT).ok()?);
+        rest = stripped[end + 1..].trim_start();
+    }
+
+    let mut thread = None;
+    if let Some(stripped) = rest.strip_prefix("T[") {
+        let end = stripped.find("] ")?;
+        thread = Some(stripped[..end].trim_matches('"').to_string());
+        rest = &stripped[end + 2..];
+    }
+
+    let (level, tail) = rest.split_once(' ').unwrap_or((rest, ""));
+    // `Level::from_str` ignores the case, which would take continuation lines
+    // like "Error happened here" for new records
+    let level = match level {
+        "ERROR" => Level::Error,
+        "WARN" => Level::Warn,
+        "INFO" => Level::Info,
+        "DEBUG" => Level::Debug,
+        "TRACE" => Level::Trace,
+        _ => return None,
+    };
+    rest = tail;
+
+    let mut target = String::new();
+    let mut location = None;
+    if let Some(stripped) = rest.strip_prefix('[') {
+        if let Some(end) = stripped.find("] ") {
+            let inner = &stripped[..end];
+            rest = &stripped[end + 2..];
+            match split_location(inner) {
+                Some(loc) => location = Some(loc),
+                None => {
+                    target = inner.to_string();
+                    if let Some((loc, tail)) = rest
+                        .split_once(": ")
+                        .and_then(|(loc, tail)| Some((split_location(loc)?, tail)))
+                    {
+                        location = Some(loc);
+                        rest = tail;
+                    }
+                }
+            }
+        }
+    }
+
+    let (file, line) = match location {
+        Some((file, line)) => (Some(file), Some(line)),
+        None => (None, None),
+    };
+
+    Some(LogEntry {
+        seq: 0,
+        time: time.unwrap_or_else(|| *DeferredNow::new().now()),
+        level,
+        thread: thread.unwrap_or_else(|| "(unnamed)".to_string()),
+        target,
+        file,
+        line,
+        message: rest.to_string(),
+        source: None,
+        fields: Vec::new(),
+        json_start: None,
+    })
+}
+
+/// Split a `file:line` location.
+fn split_location(location: &str) -> Option<(String, u32)> {
+    let (file, line) = location.rsplit_once(':')?;
+    Some((file.to_string(), line.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "cursive-flexi-follow-{}-{}",
+            std::process::id(),
+            name
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn follow(path: &Path) -> FollowState {
+        FollowState {
+            follower: FileFollower::new(path)
+                .store(LogStore::new())
+                .from_beginning(),
+            source: "test".to_string(),
+            sink: Cursive::new().cb_sink().clone(),
+            file: None,
+            partial: Vec::new(),
+            pending: None,
+        }
+    }
+
+    fn append(path: &Path, content: &str) {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+    }
+
+    fn poll(state: &mut FollowState) -> Vec<String> {
+        let mut entries = Vec::new();
+        state.poll(&mut entries, false);
+        entries.into_iter().map(|entry| entry.message).collect()
+    }
+
+    #[test]
+    fn parses_detailed_format() {
+        let entry =
+            parse_line("[2016-01-13 15:25:01.640870 +01:00] WARN [src/foo/bar.rs:26] message")
+                .unwrap();
+        assert_eq!(entry.level, Level::Warn);
+        assert_eq!(entry.time.year(), 2016);
+        assert_eq!(entry.file.as_deref(), Some("src/foo/bar.rs"));
+        assert_eq!(entry.line, Some(26));
+        assert_eq!(entry.message, "message");
+    }
+
+    #[test]
+    fn parses_thread_and_target() {
+        let entry = parse_line(
+            "[2016-01-13 15:25:01.640870 +01:00] T[\"worker\"] DEBUG [foo::bar] src/foo/bar.rs:26: a: b",
+        )
+        .unwrap();
+        assert_eq!(entry.level, Level::Debug);
+        assert_eq!(entry.thread, "worker");
+        assert_eq!(entry.target, "foo::bar");
+        assert_eq!(entry.line, Some(26));
+        assert_eq!(entry.message, "a: b");
+
+        let entry = parse_line("INFO [my_prog::module] message").unwrap();
+        assert_eq!(entry.target, "my_prog::module");
+        assert_eq!(entry.file, None);
+        assert_eq!(entry.message, "message");
+    }
+
+    #[test]
+    fn parses_colored_lines() {
+        let line = strip_ansi("\x1b[1;31mERROR\x1b[0m [foo] \x1b[1;31mbroken\x1b[0m");
+        let entry = parse_line(&line).unwrap();
+        assert_eq!(entry.level, Level::Error);
+        assert_eq!(entry.message, "broken");
+    }
+
+    #[test]
+    fn level_words_continue_records() {
+        assert!(parse_line("Error happened here").is_none());
+        assert!(parse_line("info: nothing to see").is_none());
+        assert!(parse_line("  at src/main.rs:3").is_none());
+        assert!(parse_line("[not a timestamp] INFO message").is_none());
+    }
+
+    #[test]
+    fn continuation_lines_join_the_record() {
+        let dir = temp_dir("continuation");
+        let path = dir.join("app.log");
+        append(
+            &path,
+            "ERROR [app] failed\nError happened here\nINFO [app] next\n",
+        );
+
+        let mut state = follow(&path);
+        assert_eq!(
+            poll(&mut state),
+            vec![
+                "failed\nError happened here".to_string(),
+                "next".to_string()
+            ]
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn truncated_files_are_read_from_the_start() {
+        let dir = temp_dir("truncate");
+        let path = dir.join("app.log");
+        append(&path, "INFO [app] first\nINFO [app] second\n");
+
+        let mut state = follow(&path);
+        assert_eq!(poll(&mut state), vec!["first", "second"]);
+        assert!(poll(&mut state).is_empty());
+
+        fs::write(&path, "INFO [app] third\n").unwrap();
+        assert_eq!(poll(&mut state), vec!["third"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn follows_rotated_current_file() {
+        let dir = temp_dir("rotate");
+        let path = dir.join("app.log");
+        let current = dir.join("app_rCURRENT.log");
+        assert_eq!(resolve_path(&path), None);
+
+        append(&current, "INFO [app] first\n");
+        assert_eq!(resolve_path(&path), Some(current.clone()));
+        let mut state = follow(&path);
+        assert_eq!(poll(&mut state), vec!["first"]);
+
+        // rotate, the rest of the old file is read before the new one
+        append(&current, "INFO [app] second\n");
+        fs::rename(&current, dir.join("app_r00000.log")).unwrap();
+        append(&current, "INFO [app] third\n");
+        assert_eq!(poll(&mut state), vec!["second", "third"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
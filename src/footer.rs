@@ -0,0 +1,107 @@
+use cursive_core::theme::Effect;
+use cursive_core::view::{View, ViewWrapper};
+use cursive_core::views::ScrollView;
+use cursive_core::{Printer, Rect, Vec2};
+
+use crate::{perform_scroll, FlexiLoggerView};
+
+/// A scrollable `FlexiLoggerView` with a status line below it.
+///
+/// The status line shows whether the view follows new records or is paused,
+/// the active filters, how many of the retained records are shown, and how
+/// many records were dropped.
+///
+/// Wrapped log views are interactive, unless they were made non-interactive
+/// with `FlexiLoggerView::interactive`. Like `FlexiLoggerView::into_scrollable`,
+/// the footer view sets the `on_scroll_inner` callback of the `ScrollView` to
+/// perform the scroll actions of the keymap.
+///
+/// # Show a log view with a status line
+///
+/// ```rust
+/// use cursive::{Cursive, CursiveExt};
+/// use cursive_flexi_logger_view::{FlexiLoggerView, LogFooterView};
+///
+/// fn main() {
+///     let mut siv = Cursive::default();
+///
+///     siv.add_layer(LogFooterView::new(FlexiLoggerView::scrollable()));
+///
+///     // siv.run();
+/// }
+/// ```
+pub struct LogFooterView {
+    view: ScrollView<FlexiLoggerView>,
+}
+
+impl LogFooterView {
+    /// Add a status line below `view`.
+    pub fn new(mut view: ScrollView<FlexiLoggerView>) -> Self {
+        view.get_inner_mut().wants_keys = true;
+        view.set_on_scroll_inner(perform_scroll);
+        LogFooterView { view }
+    }
+
+    cursive_core::inner_getters!(self.view: ScrollView<FlexiLoggerView>);
+
+    /// Render the status line.
+    fn status(&self) -> String {
+        let view = self.view.get_inner();
+        let filter = view.filter();
+        let (visible, total) = view.record_counts();
+
+        let mut parts = vec![if self.view.is_at_bottom() {
+            "FOLLOW".to_string()
+        } else {
+            "PAUSED".to_string()
+        }];
+        if let Some(level) = filter.level() {
+            parts.push(format!("level {}", level));
+        }
+        if let Some(target) = filter.target() {
+            parts.push(format!("target {}", target));
+        }
+        if !filter.query().is_empty() {
+            parts.push(format!("query \"{}\"", filter.query()));
+        }
+        let hidden = filter.hidden_threads().count();
+        if hidden > 0 {
+            parts.push(format!("{} threads hidden", hidden));
+        }
+        parts.push(format!("{}/{} records", visible, total));
+
+        let store = view.store();
+        let dropped = store.evicted_records() + store.dropped_records();
+        if dropped > 0 {
+            parts.push(format!("{} dropped", dropped));
+        }
+        format!(" {} ", parts.join(" │ "))
+    }
+}
+
+impl ViewWrapper for LogFooterView {
+    cursive_core::wrap_impl!(self.view: ScrollView<FlexiLoggerView>);
+
+    fn wrap_draw(&self, printer: &Printer<'_, '_>) {
+        let height = printer.size.y.saturating_sub(1);
+        self.view.draw(&printer.cropped((printer.size.x, height)));
+
+        let footer = printer.offset((0, height)).cropped((printer.size.x, 1));
+        footer.with_effect(Effect::Reverse, |printer| {
+            printer.print_hline((0, 0), printer.size.x, " ");
+            printer.print((0, 0), &self.status());
+        });
+    }
+
+    fn wrap_layout(&mut self, size: Vec2) {
+        self.view.layout(size.saturating_sub((0, 1)));
+    }
+
+    fn wrap_required_size(&mut self, constraint: Vec2) -> Vec2 {
+        self.view.required_size(constraint.saturating_sub((0, 1))) + (0, 1)
+    }
+
+    fn wrap_important_area(&self, size: Vec2) -> Rect {
+        self.view.important_area(size.saturating_sub((0, 1)))
+    }
+}
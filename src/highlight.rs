@@ -0,0 +1,113 @@
+use cursive_core::theme::Style;
+use cursive_core::utils::markup::StyledString;
+
+#[derive(Clone, Debug)]
+enum Pattern {
+    Substring(String),
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+impl Pattern {
+    /// Returns the byte ranges of the matches in `text`.
+    fn matches(&self, text: &str) -> Vec<(usize, usize)> {
+        match self {
+            Pattern::Substring(needle) if needle.is_empty() => Vec::new(),
+            Pattern::Substring(needle) => text
+                .match_indices(needle.as_str())
+                .map(|(start, found)| (start, start + found.len()))
+                .collect(),
+            #[cfg(feature = "regex")]
+            Pattern::Regex(regex) => regex
+                .find_iter(text)
+                .filter(|found| !found.as_str().is_empty())
+                .map(|found| (found.start(), found.end()))
+                .collect(),
+        }
+    }
+}
+
+/// Highlights the parts of log messages matching a pattern, e.g. request ids
+/// or error codes, with a `Style`.
+///
+/// The style is applied on top of the level color of the message, so a rule
+/// with only an effect keeps the color and a rule with only a background
+/// keeps the foreground. Rules apply in the order they were added to a
+/// `FlexiLoggerView`.
+///
+/// # Highlight timeouts and request ids
+///
+/// ```rust
+/// use cursive::theme::{BaseColor, Color, Effect};
+/// use cursive::{Cursive, CursiveExt};
+/// use cursive_flexi_logger_view::{FlexiLoggerView, HighlightRule};
+///
+/// fn main() {
+///     let mut siv = Cursive::default();
+///
+///     siv.add_layer(
+///         FlexiLoggerView::new()
+///             .with_highlight(HighlightRule::new("timeout", Effect::Bold))
+///             .with_highlight(HighlightRule::new("req-", Color::Light(BaseColor::Cyan)))
+///             .into_scrollable(),
+///     );
+///
+///     // siv.run();
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct HighlightRule {
+    pattern: Pattern,
+    style: Style,
+}
+
+impl HighlightRule {
+    /// Create a rule highlighting each occurrence of `pattern`, matched case
+    /// sensitively.
+    pub fn new<S: Into<String>, T: Into<Style>>(pattern: S, style: T) -> Self {
+        HighlightRule {
+            pattern: Pattern::Substring(pattern.into()),
+            style: style.into(),
+        }
+    }
+
+    /// Create a rule highlighting each match of the regular expression
+    /// `pattern`, e.g. `\b\d{1,3}(\.\d{1,3}){3}\b` for IPv4 addresses.
+    #[cfg(feature = "regex")]
+    pub fn regex<T: Into<Style>>(pattern: &str, style: T) -> Result<Self, regex::Error> {
+        Ok(HighlightRule {
+            pattern: Pattern::Regex(regex::Regex::new(pattern)?),
+            style: style.into(),
+        })
+    }
+
+    /// Returns the style of the matches.
+    pub fn style(&self) -> Style {
+        self.style
+    }
+
+    /// Returns `line` with the matches of this rule restyled.
+    fn apply(&self, line: &StyledString) -> StyledString {
+        let mut highlighted = StyledString::new();
+        for span in line.spans() {
+            let mut end = 0;
+            for (start, stop) in self.pattern.matches(span.content) {
+                if start > end {
+                    highlighted.append_styled(&span.content[end..start], *span.attr);
+                }
+                highlighted
+                    .append_styled(&span.content[start..stop], span.attr.combine(self.style));
+                end = stop;
+            }
+            if end < span.content.len() {
+                highlighted.append_styled(&span.content[end..], *span.attr);
+            }
+        }
+        highlighted
+    }
+}
+
+/// Apply `rules` to `line`, one after another.
+pub(crate) fn highlight(line: StyledString, rules: &[HighlightRule]) -> StyledString {
+    rules.iter().fold(line, |line, rule| rule.apply(&line))
+}
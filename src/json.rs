@@ -0,0 +1,171 @@
+use cursive_core::theme::{BaseColor, Color, Style};
+use cursive_core::utils::markup::StyledString;
+use serde_json::Value;
+
+const KEY: Color = Color::Dark(BaseColor::Cyan);
+const STRING: Color = Color::Dark(BaseColor::Green);
+const NUMBER: Color = Color::Dark(BaseColor::Magenta);
+const LITERAL: Color = Color::Dark(BaseColor::Yellow);
+
+/// Split `message` into leading text and a JSON object or array, which makes
+/// up the rest of the message. Returns `None` if the message does not end
+/// with JSON.
+pub(crate) fn split_json(message: &str) -> Option<(&str, &str)> {
+    let trimmed = message.trim_end();
+    if !trimmed.ends_with('}') && !trimmed.ends_with(']') {
+        return None;
+    }
+    trimmed
+        .char_indices()
+        .filter(|(_, c)| *c == '{' || *c == '[')
+        .map(|(index, _)| trimmed.split_at(index))
+        .find(|(_, json)| serde_json::from_str::<Value>(json).is_ok())
+}
+
+/// Append the syntax colored `json` to `line`. Collapsed JSON is printed on a
+/// single line, expanded JSON as an indented tree.
+///
+/// `json` has to be valid, the keys keep the order they were logged in.
+pub(crate) fn append_json(line: &mut StyledString, json: &str, expanded: bool, plain: Style) {
+    let mut depth = 0;
+    let mut tokens = Tokens { rest: json }.peekable();
+    let newline = |line: &mut StyledString, depth: usize| {
+        line.append_styled(format!("\n{}", "  ".repeat(depth)), plain);
+    };
+
+    while let Some(token) = tokens.next() {
+        match token {
+            "{" | "[" => {
+                line.append_styled(token, plain);
+                match tokens.peek() {
+                    Some(&"}") | Some(&"]") => {
+                        line.append_styled(tokens.next().unwrap_or_default(), plain);
+                    }
+                    _ => {
+                        depth += 1;
+                        if expanded {
+                            newline(line, depth);
+                        }
+                    }
+                }
+            }
+            "}" | "]" => {
+                depth = depth.saturating_sub(1);
+                if expanded {
+                    newline(line, depth);
+                }
+                line.append_styled(token, plain);
+            }
+            "," if expanded => {
+                line.append_styled(",", plain);
+                newline(line, depth);
+            }
+            "," => line.append_styled(", ", plain),
+            ":" => line.append_styled(": ", plain),
+            _ if token.starts_with('"') => {
+                let color = if tokens.peek() == Some(&":") {
+                    KEY
+                } else {
+                    STRING
+                };
+                line.append_styled(token, color);
+            }
+            "true" | "false" | "null" => line.append_styled(token, LITERAL),
+            _ => line.append_styled(token, NUMBER),
+        }
+    }
+}
+
+/// Splits valid JSON into strings, literals, numbers and punctuation,
+/// skipping whitespace.
+struct Tokens<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        self.rest = self.rest.trim_start();
+        let mut chars = self.rest.char_indices();
+        let end = match chars.next()?.1 {
+            '{' | '}' | '[' | ']' | ',' | ':' => 1,
+            '"' => {
+                let mut escaped = false;
+                chars
+                    .find(|(_, c)| {
+                        let end = *c == '"' && !escaped;
+                        escaped = *c == '\\' && !escaped;
+                        end
+                    })
+                    .map_or(self.rest.len(), |(index, _)| index + 1)
+            }
+            _ => chars
+                .find(|(_, c)| c.is_whitespace() || "{}[],:".contains(*c))
+                .map_or(self.rest.len(), |(index, _)| index),
+        };
+        let (token, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        Some(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(json: &str) -> Vec<&str> {
+        Tokens { rest: json }.collect()
+    }
+
+    #[test]
+    fn splits_text_and_json() {
+        assert_eq!(
+            split_json(r#"request done {"status": 200}  "#),
+            Some(("request done ", r#"{"status": 200}"#))
+        );
+        assert_eq!(split_json("[1, 2]"), Some(("", "[1, 2]")));
+        // the first bracket which starts valid JSON is taken
+        assert_eq!(
+            split_json(r#"got {x} and {"y": [1]}"#),
+            Some(("got {x} and ", r#"{"y": [1]}"#))
+        );
+        assert_eq!(split_json("no json here"), None);
+    }
+
+    #[test]
+    fn ignores_bracketed_text() {
+        assert_eq!(split_json("listening on [::1]"), None);
+        assert_eq!(split_json("state {connected}"), None);
+        assert_eq!(
+            split_json(r#"[::1] replied [true, null]"#),
+            Some(("[::1] replied ", "[true, null]"))
+        );
+    }
+
+    #[test]
+    fn tokenizes_strings_with_escaped_quotes() {
+        assert_eq!(
+            tokens(r#"{"say": "a \"quoted\" word", "path": "C:\\"}"#),
+            [
+                "{",
+                r#""say""#,
+                ":",
+                r#""a \"quoted\" word""#,
+                ",",
+                r#""path""#,
+                ":",
+                r#""C:\\""#,
+                "}"
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenizes_literals_and_numbers() {
+        assert_eq!(
+            tokens("[ true,false ,null, -1.5e3 ]"),
+            ["[", "true", ",", "false", ",", "null", ",", "-1.5e3", "]"]
+        );
+    }
+}
@@ -0,0 +1,238 @@
+use cursive_core::event::{Event, Key};
+
+use std::collections::HashMap;
+
+/// An interaction of a `FlexiLoggerView` which can be bound to an `Event`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LogViewAction {
+    /// Select the previous record.
+    SelectPrevious,
+    /// Select the next record.
+    SelectNext,
+    /// Call the `on_activate` callback with the selected record.
+    Activate,
+    /// Unfold the selected record.
+    Expand,
+    /// Fold the selected record.
+    Collapse,
+    /// Fold or unfold all records.
+    ToggleCollapseAll,
+    /// Bookmark the selected record, or remove its bookmark.
+    ToggleBookmark,
+    /// Select the next bookmarked record.
+    NextBookmark,
+    /// Select the previous bookmarked record.
+    PreviousBookmark,
+    /// Scroll up by one row.
+    ScrollUp,
+    /// Scroll down by one row.
+    ScrollDown,
+    /// Scroll up by one page.
+    PageUp,
+    /// Scroll down by one page.
+    PageDown,
+    /// Scroll to the oldest record.
+    ScrollToTop,
+    /// Scroll to the newest record and keep following new records.
+    Follow,
+    /// Show an input for the filter query, see `LogFilter::set_query`.
+    Search,
+    /// Show a checklist of the threads to show or hide.
+    Filter,
+    /// Remove all records from the store of the view. Not bound by default.
+    Clear,
+    /// Write the shown records to the export file of the view.
+    Export,
+    /// Switch to the next pane of a `DebugConsole`.
+    NextPane,
+    /// Switch to the previous pane of a `DebugConsole`.
+    PreviousPane,
+    /// Show the `LogSpecEditor` of a `DebugConsole`.
+    EditSpec,
+    /// Restore the log specification replaced by the `LogSpecEditor` of a
+    /// `DebugConsole`.
+    RestoreSpec,
+}
+
+/// Maps `Event`s to the `LogViewAction`s of a `FlexiLoggerView`.
+///
+/// Only interactive views handle events, other views leave them to the
+/// application, see `FlexiLoggerView::interactive`. The scroll actions and
+/// `Follow` need the `ScrollView` of `FlexiLoggerView::into_scrollable` or
+/// `LogFooterView`, and the pane and spec actions are handled by the
+/// `DebugConsole`. Keys which are not bound still reach the
+/// `ScrollView`, which scrolls on the arrow and page keys by itself.
+///
+/// Besides the default bindings, there are presets for vim and less users.
+/// Each of them may be changed by binding or unbinding events.
+///
+/// # Use vim keys, but keep `/` for the application
+///
+/// ```rust
+/// use cursive::{Cursive, CursiveExt};
+/// use cursive_flexi_logger_view::{FlexiLoggerView, LogViewAction, LogViewKeymap};
+///
+/// fn main() {
+///     let mut siv = Cursive::default();
+///
+///     let keymap = LogViewKeymap::vim()
+///         .unbind('/')
+///         .bind('s', LogViewAction::Search);
+///     siv.add_layer(
+///         FlexiLoggerView::new()
+///             .with_bookmarks()
+///             .with_keymap(keymap)
+///             .into_scrollable(),
+///     );
+///
+///     // siv.run();
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct LogViewKeymap {
+    bindings: HashMap<Event, LogViewAction>,
+}
+
+impl LogViewKeymap {
+    /// Create the default keymap. `Clear` removes the records of all views of
+    /// the store, so it is not bound by default, see `bind`.
+    ///
+    /// | Action | Events |
+    /// |---|---|
+    /// | `SelectPrevious`, `SelectNext` | `Up`, `Down` |
+    /// | `Activate` | `Enter` |
+    /// | `Expand`, `Collapse` | `Right`, `Left` |
+    /// | `ToggleCollapseAll` | `z` |
+    /// | `ToggleBookmark` | `m` |
+    /// | `NextBookmark`, `PreviousBookmark` | `]`, `[` |
+    /// | `PageUp`, `PageDown` | `PageUp`, `PageDown` |
+    /// | `ScrollToTop`, `Follow` | `Home`, `End` |
+    /// | `Search` | `/` |
+    /// | `Filter` | `t` |
+    /// | `Export` | `Ctrl-S` |
+    /// | `NextPane`, `PreviousPane` | `Ctrl-N`, `Ctrl-P` |
+    /// | `EditSpec`, `RestoreSpec` | `v`, `V` |
+    pub fn new() -> Self {
+        Self::empty()
+            .bind(Key::Up, LogViewAction::SelectPrevious)
+            .bind(Key::Down, LogViewAction::SelectNext)
+            .bind(Key::Enter, LogViewAction::Activate)
+            .bind(Key::Right, LogViewAction::Expand)
+            .bind(Key::Left, LogViewAction::Collapse)
+            .bind('z', LogViewAction::ToggleCollapseAll)
+            .bind('m', LogViewAction::ToggleBookmark)
+            .bind(']', LogViewAction::NextBookmark)
+            .bind('[', LogViewAction::PreviousBookmark)
+            .bind(Key::PageUp, LogViewAction::PageUp)
+            .bind(Key::PageDown, LogViewAction::PageDown)
+            .bind(Key::Home, LogViewAction::ScrollToTop)
+            .bind(Key::End, LogViewAction::Follow)
+            .bind('/', LogViewAction::Search)
+            .bind('t', LogViewAction::Filter)
+            .bind(Event::CtrlChar('s'), LogViewAction::Export)
+            .bind(Event::CtrlChar('n'), LogViewAction::NextPane)
+            .bind(Event::CtrlChar('p'), LogViewAction::PreviousPane)
+            .bind('v', LogViewAction::EditSpec)
+            .bind('V', LogViewAction::RestoreSpec)
+    }
+
+    /// Create a keymap without any binding.
+    pub fn empty() -> Self {
+        LogViewKeymap {
+            bindings: HashMap::new(),
+        }
+    }
+
+    /// Create a keymap for vim users. In addition to the default bindings,
+    /// `k`/`j` select, `l`/`h` unfold and fold, `Ctrl-Y`/`Ctrl-E` scroll by a
+    /// row, `Ctrl-U`/`Ctrl-B` and `Ctrl-D`/`Ctrl-F` scroll by a page, `g`
+    /// scrolls to the top, `G` follows new records, `f` filters threads and
+    /// `w` exports.
+    pub fn vim() -> Self {
+        Self::new()
+            .unbind('t')
+            .bind('k', LogViewAction::SelectPrevious)
+            .bind('j', LogViewAction::SelectNext)
+            .bind('l', LogViewAction::Expand)
+            .bind('h', LogViewAction::Collapse)
+            .bind(Event::CtrlChar('y'), LogViewAction::ScrollUp)
+            .bind(Event::CtrlChar('e'), LogViewAction::ScrollDown)
+            .bind(Event::CtrlChar('u'), LogViewAction::PageUp)
+            .bind(Event::CtrlChar('b'), LogViewAction::PageUp)
+            .bind(Event::CtrlChar('d'), LogViewAction::PageDown)
+            .bind(Event::CtrlChar('f'), LogViewAction::PageDown)
+            .bind('g', LogViewAction::ScrollToTop)
+            .bind('G', LogViewAction::Follow)
+            .bind('f', LogViewAction::Filter)
+            .bind('w', LogViewAction::Export)
+    }
+
+    /// Create a keymap for less users. In addition to the default bindings,
+    /// `k`/`y` and `j`/`e` scroll by a row, `b` and `Space`/`f` scroll by a
+    /// page, `g`/`<` scroll to the top, `G`/`>`/`F` follow new records, `&`
+    /// filters threads, `s` exports and `Z` folds or unfolds all records.
+    pub fn less() -> Self {
+        Self::new()
+            .unbind('z')
+            .unbind('t')
+            .bind('k', LogViewAction::ScrollUp)
+            .bind('y', LogViewAction::ScrollUp)
+            .bind('j', LogViewAction::ScrollDown)
+            .bind('e', LogViewAction::ScrollDown)
+            .bind('b', LogViewAction::PageUp)
+            .bind(' ', LogViewAction::PageDown)
+            .bind('f', LogViewAction::PageDown)
+            .bind('g', LogViewAction::ScrollToTop)
+            .bind('<', LogViewAction::ScrollToTop)
+            .bind('G', LogViewAction::Follow)
+            .bind('>', LogViewAction::Follow)
+            .bind('F', LogViewAction::Follow)
+            .bind('&', LogViewAction::Filter)
+            .bind('s', LogViewAction::Export)
+            .bind('Z', LogViewAction::ToggleCollapseAll)
+    }
+
+    /// Bind `event` to `action`, replacing its previous binding.
+    pub fn bind<E: Into<Event>>(mut self, event: E, action: LogViewAction) -> Self {
+        self.set_binding(event, Some(action));
+        self
+    }
+
+    /// Remove the binding of `event`.
+    pub fn unbind<E: Into<Event>>(mut self, event: E) -> Self {
+        self.set_binding(event, None);
+        self
+    }
+
+    /// Bind `event` to `action`, or remove its binding if `action` is `None`.
+    pub fn set_binding<E: Into<Event>>(&mut self, event: E, action: Option<LogViewAction>) {
+        match action {
+            Some(action) => self.bindings.insert(event.into(), action),
+            None => self.bindings.remove(&event.into()),
+        };
+    }
+
+    /// Remove all bindings of `action`.
+    pub fn unbind_action(&mut self, action: LogViewAction) {
+        self.bindings.retain(|_, bound| *bound != action);
+    }
+
+    /// Returns the action bound to `event`.
+    pub fn action(&self, event: &Event) -> Option<LogViewAction> {
+        self.bindings.get(event).copied()
+    }
+
+    /// Returns the events bound to `action`.
+    pub fn events(&self, action: LogViewAction) -> impl Iterator<Item = &Event> {
+        self.bindings
+            .iter()
+            .filter(move |(_, bound)| **bound == action)
+            .map(|(event, _)| event)
+    }
+}
+
+impl Default for LogViewKeymap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,178 @@
+use cursive_core::theme::Effect;
+use cursive_core::utils::markup::StyledString;
+use cursive_core::view::View;
+use cursive_core::{Printer, Vec2};
+use flexi_logger::Level;
+use time::OffsetDateTime;
+use unicode_width::UnicodeWidthChar;
+
+use std::convert::TryFrom;
+use std::time::Duration;
+
+use crate::{append_fields, level_color, styled_entry, LogEntry, LogStore};
+
+/// A single line showing the most recent log record, e.g. as a status ticker
+/// below the content of a screen.
+///
+/// The record is formatted like in a `FlexiLoggerView` and truncated with an
+/// ellipsis if it does not fit. Records can be faded and cleared once they
+/// reach a certain age. As cursive only redraws on events, enable
+/// `Cursive::set_autorefresh` to fade or clear records while nothing is
+/// logged.
+///
+/// # Show the latest warning for ten seconds
+///
+/// ```rust
+/// use cursive::views::{LinearLayout, TextView};
+/// use cursive::{Cursive, CursiveExt};
+/// use cursive_flexi_logger_view::LastLogView;
+/// use flexi_logger::Level;
+///
+/// use std::time::Duration;
+///
+/// fn main() {
+///     let mut siv = Cursive::default();
+///     siv.set_autorefresh(true);
+///
+///     siv.add_fullscreen_layer(
+///         LinearLayout::vertical()
+///             .child(TextView::new("content"))
+///             .child(
+///                 LastLogView::new()
+///                     .level(Level::Warn)
+///                     .fade_after(Duration::from_secs(5))
+///                     .clear_after(Duration::from_secs(10)),
+///             ),
+///     );
+///
+///     // siv.run();
+/// }
+/// ```
+pub struct LastLogView {
+    store: LogStore,
+    level: Level,
+    fade_after: Option<Duration>,
+    clear_after: Option<Duration>,
+}
+
+impl LastLogView {
+    /// Create a view showing the most recent record of the global `LogStore`.
+    pub fn new() -> Self {
+        Self::with_store(LogStore::global())
+    }
+
+    /// Create a view showing the most recent record of `store`.
+    pub fn with_store(store: LogStore) -> Self {
+        LastLogView {
+            store,
+            level: Level::Trace,
+            fade_after: None,
+            clear_after: None,
+        }
+    }
+
+    /// Only show records which are at least as severe as `level`.
+    pub fn level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Set the least severe level of the records to show.
+    pub fn set_level(&mut self, level: Level) {
+        self.level = level;
+    }
+
+    /// Dim the record once it is older than `age`.
+    pub fn fade_after(mut self, age: Duration) -> Self {
+        self.fade_after = Some(age);
+        self
+    }
+
+    /// Hide the record once it is older than `age`.
+    pub fn clear_after(mut self, age: Duration) -> Self {
+        self.clear_after = Some(age);
+        self
+    }
+
+    /// Render the most recent record which is severe enough, together with
+    /// its age.
+    fn latest(&self) -> Option<(StyledString, Duration)> {
+        let logs = self.store.lock();
+        let entry = logs.iter().rev().find(|entry| entry.level <= self.level)?;
+        let age = Duration::try_from(OffsetDateTime::now_utc() - entry.time).unwrap_or_default();
+        Some((render(entry), age))
+    }
+}
+
+impl Default for LastLogView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl View for LastLogView {
+    fn draw(&self, printer: &Printer<'_, '_>) {
+        let (line, age) = match self.latest() {
+            Some(latest) => latest,
+            None => return,
+        };
+        if self.clear_after.is_some_and(|clear| age >= clear) {
+            return;
+        }
+        let line = truncate(&line, printer.size.x);
+        if self.fade_after.is_some_and(|fade| age >= fade) {
+            printer.with_effect(Effect::Dim, |printer| printer.print_styled((0, 0), &line));
+        } else {
+            printer.print_styled((0, 0), &line);
+        }
+    }
+
+    fn required_size(&mut self, constraint: Vec2) -> Vec2 {
+        Vec2::new(constraint.x, 1)
+    }
+}
+
+/// Render `entry` on a single line. Further lines of the message are replaced
+/// by a marker like `[+59 lines]`.
+fn render(entry: &LogEntry) -> StyledString {
+    let mut line = styled_entry(entry, None);
+    let mut lines = entry.message.lines();
+    line.append_styled(lines.next().unwrap_or_default(), level_color(entry.level));
+    let more = lines.count();
+    if more > 0 {
+        line.append_styled(format!(" [+{} lines]", more), Effect::Dim);
+    }
+    append_fields(&mut line, entry);
+    line
+}
+
+/// Truncate `line` to `width` columns, ending it with an ellipsis if it is cut.
+fn truncate(line: &StyledString, width: usize) -> StyledString {
+    if line.width() <= width {
+        return line.clone();
+    }
+
+    let mut out = StyledString::new();
+    let mut used = 0;
+    for span in line.spans() {
+        let mut content = String::new();
+        let mut full = false;
+        for c in span.content.chars() {
+            let w = c.width().unwrap_or(0);
+            if used + w + 1 > width {
+                full = true;
+                break;
+            }
+            content.push(c);
+            used += w;
+        }
+        out.append_styled(content, *span.attr);
+        if full {
+            break;
+        }
+    }
+    if width > 0 {
+        out.append_plain("…");
+    }
+    out
+}
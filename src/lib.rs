@@ -46,6 +46,9 @@
 //!
 //! Look into the `FlexiLoggerView` documentation for a detailed explanation.
 //!
+//! The `setup` function starts such a logger in one call, configured by
+//! `SetupOptions`, and returns its `LoggerHandle` and `LogStore`.
+//!
 //! ## Add toggleable flexi_logger debug console view
 //!
 //! This crate also provide utility functions, which is simplify usage of `FlexiLoggerView`, providing
@@ -88,25 +91,88 @@
 //! }
 //! ```
 
-use arraydeque::{ArrayDeque, Wrapping};
-use cursive_core::theme::{BaseColor, Color};
+mod colors;
+mod columns;
+mod console;
+mod dump;
+mod editor;
+mod filter;
+mod follow;
+mod footer;
+mod highlight;
+mod json;
+mod keymap;
+mod last;
+mod limit;
+mod panic;
+mod rate;
+mod remote;
+mod setup;
+mod spec;
+mod store;
+mod terminal;
+
+pub use colors::ColorBy;
+pub use columns::{Column, ColumnLayout, ColumnWidth};
+pub use console::DebugConsole;
+pub use dump::{LogDump, LogDumpGuard};
+pub use editor::open_in_editor;
+pub use filter::LogFilter;
+pub use follow::{FileFollower, FileFollowerHandle};
+pub use footer::LogFooterView;
+pub use highlight::HighlightRule;
+pub use keymap::{LogViewAction, LogViewKeymap};
+pub use last::LastLogView;
+pub use limit::RateLimits;
+pub use panic::PanicHook;
+pub use rate::LogRateView;
+pub use remote::{LogListener, LogListenerHandle, RemoteLogWriter};
+pub use setup::{setup, SetupOptions};
+pub use spec::LogSpecEditor;
+pub use store::{LevelCounts, LogEntry, LogStore, LogSubscription};
+
+use colors::NameColors;
+use console::ConsolePanes;
+use limit::RateLimiter;
+use store::LogBuffer;
+
+use cursive_core::direction::Direction;
+use cursive_core::event::{Event, EventResult, MouseButton, MouseEvent};
+use cursive_core::theme::{BaseColor, Color, Effect, Theme};
 use cursive_core::utils::markup::StyledString;
-use cursive_core::view::{Nameable, ScrollStrategy, Scrollable, View};
-use cursive_core::views::{Dialog, ScrollView};
-use cursive_core::{CbSink, Cursive, Printer, Vec2};
+use cursive_core::view::{CannotFocus, Nameable, Resizable, ScrollStrategy, Scrollable, View};
+use cursive_core::views::{Checkbox, Dialog, EditView, ListView, ScrollView};
+use cursive_core::{CbSink, Cursive, Printer, Rect, Vec2};
 use flexi_logger::{writers::LogWriter, DeferredNow, Level, Record};
 use unicode_width::UnicodeWidthStr;
 
-use std::sync::{Arc, Mutex};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Condvar, Mutex};
 use std::thread;
+use std::time::{Duration, Instant};
+
+type ActivateCallback = Arc<dyn Fn(&mut Cursive, &LogEntry) + Send + Sync>;
 
-type LogBuffer = ArrayDeque<[StyledString; 2048], Wrapping>;
+/// Changes to the filter of a view made by its dialogs, which are applied on
+/// the next layout of the view.
+type FilterEdits = Arc<Mutex<Vec<Box<dyn FnOnce(&mut LogFilter) + Send>>>>;
 
 static FLEXI_LOGGER_DEBUG_VIEW_NAME: &str = "_flexi_debug_view";
+static FLEXI_LOGGER_DEBUG_QUERY_NAME: &str = "_flexi_debug_query";
 
-lazy_static::lazy_static! {
-    static ref LOGS: Arc<Mutex<LogBuffer>> = Arc::new(Mutex::new(LogBuffer::new()));
-}
+/// How long `flush` waits for the ingestion thread at most.
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How often `flush` checks whether the ingestion thread caught up.
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+const GUTTER_BOOKMARK: &str = "* ";
+const GUTTER_EMPTY: &str = "  ";
 
 /// The `FlexiLoggerView` displays log messages from the `cursive_flexi_logger` log target.
 /// It is safe to create multiple instances of this struct.
@@ -176,6 +242,24 @@ lazy_static::lazy_static! {
 /// ```
 pub struct FlexiLoggerView {
     pub indent: bool,
+    store: LogStore,
+    columns: Option<ColumnLayout>,
+    color_by: Option<ColorBy>,
+    filter: LogFilter,
+    selected: Option<u64>,
+    on_activate: Option<ActivateCallback>,
+    bookmarks: bool,
+    interactive: Option<bool>,
+    wants_keys: bool,
+    json: bool,
+    collapse_all: bool,
+    expanded: BTreeMap<u64, bool>,
+    keymap: LogViewKeymap,
+    scroll: Option<LogViewAction>,
+    export_path: PathBuf,
+    filter_edits: FilterEdits,
+    highlights: Vec<HighlightRule>,
+    last_size: Vec2,
 }
 
 pub trait Indentable {
@@ -186,16 +270,728 @@ pub trait Indentable {
 impl FlexiLoggerView {
     /// Create a new `FlexiLoggerView` which is wrapped in a `ScrollView`.
     pub fn scrollable() -> ScrollView<Self> {
-        FlexiLoggerView { indent: true }
-            .scrollable()
+        Self::scrollable_with_store(LogStore::global())
+    }
+
+    /// Create a new `FlexiLoggerView` displaying the entries of `store`, which
+    /// is wrapped in a `ScrollView`.
+    pub fn scrollable_with_store(store: LogStore) -> ScrollView<Self> {
+        Self::with_store(store).into_scrollable()
+    }
+
+    /// Wrap this `FlexiLoggerView` in a `ScrollView`, which sticks to the
+    /// newest messages.
+    ///
+    /// The `ScrollView` performs the scroll actions and `Follow` of the
+    /// keymap, using its `on_scroll_inner` callback. Replacing the callback
+    /// stops them from working.
+    pub fn into_scrollable(self) -> ScrollView<Self> {
+        self.scrollable()
             .scroll_x(true)
             .scroll_y(true)
             .scroll_strategy(ScrollStrategy::StickToBottom)
+            .on_scroll_inner(perform_scroll)
     }
 
     /// Create a new `FlexiLoggerView`.
     pub fn new() -> Self {
-        FlexiLoggerView { indent: true }
+        Self::with_store(LogStore::global())
+    }
+
+    /// Create a new `FlexiLoggerView` displaying the entries of `store`.
+    pub fn with_store(store: LogStore) -> Self {
+        FlexiLoggerView {
+            indent: true,
+            store,
+            columns: None,
+            color_by: None,
+            filter: LogFilter::new(),
+            selected: None,
+            on_activate: None,
+            bookmarks: false,
+            interactive: None,
+            wants_keys: false,
+            json: true,
+            collapse_all: false,
+            expanded: BTreeMap::new(),
+            keymap: LogViewKeymap::new(),
+            scroll: None,
+            export_path: PathBuf::from("logs/export.log"),
+            filter_edits: Arc::new(Mutex::new(Vec::new())),
+            highlights: Vec::new(),
+            last_size: Vec2::zero(),
+        }
+    }
+
+    /// Show the log messages in aligned columns as described by `layout`.
+    pub fn with_columns(mut self, layout: ColumnLayout) -> Self {
+        self.columns = Some(layout);
+        self
+    }
+
+    /// Switch the column mode on by passing a layout, or off by passing `None`.
+    pub fn set_columns(&mut self, layout: Option<ColumnLayout>) {
+        self.columns = layout;
+    }
+
+    /// Returns the column layout, if the view is in column mode. Use this to
+    /// show or hide columns at runtime.
+    pub fn columns_mut(&mut self) -> Option<&mut ColumnLayout> {
+        self.columns.as_mut()
+    }
+
+    /// Show `column` if it is hidden, or hide it if it is shown. Does nothing
+    /// if the view is not in column mode.
+    pub fn toggle_column(&mut self, column: Column) {
+        if let Some(layout) = &mut self.columns {
+            let visible = layout.is_visible(column);
+            layout.set_visible(column, !visible);
+        }
+    }
+
+    /// Color the thread names (or targets) with a color derived from a hash of
+    /// their name, so the records of a thread are easy to follow. The colors
+    /// are picked to be readable on the background of the current theme.
+    pub fn with_hashed_colors(mut self, by: ColorBy) -> Self {
+        self.color_by = Some(by);
+        self
+    }
+
+    /// Enable hashed colors by passing what to color, or disable them by
+    /// passing `None`.
+    pub fn set_hashed_colors(&mut self, by: Option<ColorBy>) {
+        self.color_by = by;
+    }
+
+    /// Only show the log entries passing `filter`.
+    pub fn with_filter(mut self, filter: LogFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Replace the filter deciding which log entries are shown.
+    pub fn set_filter(&mut self, filter: LogFilter) {
+        self.filter = filter;
+    }
+
+    /// Returns the filter deciding which log entries are shown.
+    pub fn filter(&self) -> &LogFilter {
+        &self.filter
+    }
+
+    /// Returns the filter deciding which log entries are shown. Use this to
+    /// change the filter at runtime.
+    pub fn filter_mut(&mut self) -> &mut LogFilter {
+        &mut self.filter
+    }
+
+    /// Returns the store this view displays.
+    pub fn store(&self) -> &LogStore {
+        &self.store
+    }
+
+    /// Returns how many entries pass the filter, and how many entries the
+    /// store retains in total.
+    pub fn record_counts(&self) -> (usize, usize) {
+        let logs = self.store.lock();
+        (self.entries(&logs).count(), logs.iter().count())
+    }
+
+    /// Returns the names of all threads which emitted a record into the store
+    /// of this view, in alphabetical order.
+    pub fn seen_threads(&self) -> Vec<String> {
+        self.store.seen_threads()
+    }
+
+    /// Set a callback which is called when the user activates a record, either
+    /// by pressing `Enter` on the selected record or by clicking on its source
+    /// location. Use `open_in_editor` to open the location in `$EDITOR`.
+    ///
+    /// Setting a callback makes the view focusable, so records can be selected
+    /// with the arrow keys, or the keys bound in the keymap of the view.
+    pub fn on_activate<F>(mut self, cb: F) -> Self
+    where
+        F: Fn(&mut Cursive, &LogEntry) + Send + Sync + 'static,
+    {
+        self.set_on_activate(cb);
+        self
+    }
+
+    /// Set a callback which is called when the user activates a record.
+    ///
+    /// See `on_activate` for details.
+    pub fn set_on_activate<F>(&mut self, cb: F)
+    where
+        F: Fn(&mut Cursive, &LogEntry) + Send + Sync + 'static,
+    {
+        self.on_activate = Some(Arc::new(cb));
+    }
+
+    /// Returns the sequence number of the selected entry, if any.
+    pub fn selected(&self) -> Option<u64> {
+        self.selected
+    }
+
+    /// Make the view handle events, or leave all of them to the application.
+    ///
+    /// Unless set explicitly, a view is interactive if it has an
+    /// `on_activate` callback or bookmarks, once a keymap was set or records
+    /// were folded with `collapse_all`, and when it is wrapped in a
+    /// `LogFooterView`. Interactive views can be focused and select records.
+    pub fn interactive(mut self, interactive: bool) -> Self {
+        self.set_interactive(interactive);
+        self
+    }
+
+    /// Make the view handle events, or leave all of them to the application.
+    pub fn set_interactive(&mut self, interactive: bool) {
+        self.interactive = Some(interactive);
+    }
+
+    /// Returns whether the view handles events, see `interactive`.
+    pub fn is_interactive(&self) -> bool {
+        self.interactive
+            .unwrap_or(self.on_activate.is_some() || self.bookmarks || self.wants_keys)
+    }
+
+    /// Handle the events of the view as bound in `keymap`, instead of the
+    /// default bindings. This makes the view interactive.
+    pub fn with_keymap(mut self, keymap: LogViewKeymap) -> Self {
+        self.set_keymap(keymap);
+        self
+    }
+
+    /// Replace the keymap of the view. This makes the view interactive.
+    pub fn set_keymap(&mut self, keymap: LogViewKeymap) {
+        self.keymap = keymap;
+        self.wants_keys = true;
+    }
+
+    /// Returns the keymap of the view.
+    pub fn keymap(&self) -> &LogViewKeymap {
+        &self.keymap
+    }
+
+    /// Returns the keymap of the view. Use this to change bindings at runtime.
+    /// Unlike `set_keymap`, this does not make the view interactive.
+    pub fn keymap_mut(&mut self) -> &mut LogViewKeymap {
+        &mut self.keymap
+    }
+
+    /// Highlight the matches of `rule` in the messages, on top of the rules
+    /// added before.
+    pub fn with_highlight(mut self, rule: HighlightRule) -> Self {
+        self.highlights.push(rule);
+        self
+    }
+
+    /// Replace the highlight rules of the view.
+    pub fn set_highlights(&mut self, rules: Vec<HighlightRule>) {
+        self.highlights = rules;
+    }
+
+    /// Returns the highlight rules of the view. Use this to change them at
+    /// runtime.
+    pub fn highlights_mut(&mut self) -> &mut Vec<HighlightRule> {
+        &mut self.highlights
+    }
+
+    /// Set the file the `Export` action writes the shown records to. Missing
+    /// directories are created. Defaults to `logs/export.log`.
+    pub fn export_to<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.export_path = path.into();
+        self
+    }
+
+    /// Set the file the `Export` action writes the shown records to.
+    pub fn set_export_path<P: Into<PathBuf>>(&mut self, path: P) {
+        self.export_path = path.into();
+    }
+
+    /// Write the records passing the filter to `out` as plain text.
+    pub fn export<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let logs = self.store.lock();
+        dump::write_entries(out, self.entries(&logs), false, "\n")
+    }
+
+    /// Enable bookmarks. Bookmarked records are marked in a gutter column and
+    /// kept in the store, even when they would be dropped otherwise.
+    ///
+    /// With bookmarks enabled the view is focusable. By default, press `m` to
+    /// toggle the bookmark on the selected record, and `]` or `[` to jump to
+    /// the next or previous bookmark.
+    pub fn with_bookmarks(mut self) -> Self {
+        self.bookmarks = true;
+        self
+    }
+
+    /// Enable or disable bookmarks.
+    ///
+    /// See `with_bookmarks` for details.
+    pub fn set_bookmarks(&mut self, enabled: bool) {
+        self.bookmarks = enabled;
+    }
+
+    /// Toggle the bookmark on the selected record.
+    pub fn toggle_bookmark(&mut self) {
+        if let Some(seq) = self.selected {
+            self.store.toggle_bookmark(seq);
+        }
+    }
+
+    /// Select the next (or previous) bookmarked record. Returns whether there
+    /// was a bookmark to jump to.
+    pub fn jump_to_bookmark(&mut self, forward: bool) -> bool {
+        let next = self.store.lock().next_bookmark(self.selected, forward);
+        if next.is_some() {
+            self.selected = next;
+        }
+        next.is_some()
+    }
+
+    /// Enable or disable the detection of JSON in log messages. If a message
+    /// ends with a JSON object or array, it is syntax colored. The JSON is
+    /// collapsed into a single line, unless the record is expanded.
+    ///
+    /// JSON detection is enabled by default.
+    pub fn json(mut self, enabled: bool) -> Self {
+        self.json = enabled;
+        self
+    }
+
+    /// Enable or disable the detection of JSON in log messages.
+    ///
+    /// See `json` for details.
+    pub fn set_json(&mut self, enabled: bool) {
+        self.json = enabled;
+    }
+
+    /// Fold all multi-line records to their first line, followed by a marker
+    /// like `[+59 lines]`. Records can still be expanded one by one.
+    pub fn collapse_all(mut self, collapse: bool) -> Self {
+        self.set_collapse_all(collapse);
+        self
+    }
+
+    /// Fold all multi-line records to their first line, or unfold all of them.
+    /// This resets records which were expanded or collapsed one by one.
+    ///
+    /// Folding records makes the view interactive, so they can be unfolded.
+    /// `z` toggles between both modes by default.
+    pub fn set_collapse_all(&mut self, collapse: bool) {
+        self.collapse_all = collapse;
+        self.expanded.clear();
+        self.wants_keys |= collapse;
+    }
+
+    /// Returns whether multi-line records are folded unless they are expanded
+    /// one by one.
+    pub fn is_collapse_all(&self) -> bool {
+        self.collapse_all
+    }
+
+    /// Expand or collapse the entry with the sequence number `seq`. Collapsed
+    /// entries are folded to their first line and show JSON on a single line.
+    ///
+    /// In an interactive view, `Right` expands the selected record and `Left`
+    /// collapses it by default.
+    pub fn set_expanded(&mut self, seq: u64, expanded: bool) {
+        self.expanded.insert(seq, expanded);
+    }
+
+    /// Returns whether the entry with the sequence number `seq` is expanded.
+    pub fn is_expanded(&self, seq: u64) -> bool {
+        let logs = self.store.lock();
+        let entry = logs.position(seq).and_then(|index| logs.get(index));
+        entry.is_some_and(|entry| self.expansion(entry))
+    }
+
+    /// Returns whether `entry` is expanded. Unless set explicitly, JSON is
+    /// collapsed and other messages follow the collapse all mode.
+    ///
+    /// Collapsed JSON is shown on a single line, but only folds its record if
+    /// the record was collapsed explicitly or by the collapse all mode, see
+    /// `folded`.
+    fn expansion(&self, entry: &LogEntry) -> bool {
+        match self.expanded.get(&entry.seq) {
+            Some(expanded) => *expanded,
+            None if self.collapse_all => false,
+            None => !self.json || entry.json_start.is_none(),
+        }
+    }
+
+    /// Returns whether the multi-line message of `entry` is folded to its
+    /// first line.
+    fn folded(&self, entry: &LogEntry) -> bool {
+        match self.expanded.get(&entry.seq) {
+            Some(expanded) => !expanded,
+            None => self.collapse_all,
+        }
+    }
+
+    /// Expand or collapse the selected record, if that changes anything.
+    fn expand_selected(&mut self, expanded: bool) -> EventResult {
+        let logs = self.store.lock();
+        let entry = self
+            .selected
+            .and_then(|seq| logs.position(seq))
+            .and_then(|index| logs.get(index));
+        let explicit = |entry: &LogEntry| self.expanded.get(&entry.seq).copied();
+        let foldable = entry.filter(|entry| {
+            if expanded {
+                // unfold the record, or show its JSON as a tree
+                explicit(entry) != Some(true)
+                    && (has_newlines(entry) || self.json && entry.json_start.is_some())
+            } else {
+                // fold the record, or collapse its JSON tree
+                explicit(entry) != Some(false) && self.rows(entry) > 1
+            }
+        });
+        match foldable.map(|entry| entry.seq) {
+            Some(seq) => {
+                drop(logs);
+                self.set_expanded(seq, expanded);
+                EventResult::Consumed(None)
+            }
+            None => EventResult::Ignored,
+        }
+    }
+
+    /// Perform `action` like the event bound to it. The scroll actions are
+    /// only recorded here, the `ScrollView` performs them in
+    /// `perform_scroll`.
+    fn perform(&mut self, action: LogViewAction) -> EventResult {
+        match action {
+            LogViewAction::ScrollUp
+            | LogViewAction::ScrollDown
+            | LogViewAction::PageUp
+            | LogViewAction::PageDown
+            | LogViewAction::ScrollToTop
+            | LogViewAction::Follow => {
+                self.scroll = Some(action);
+                EventResult::Consumed(None)
+            }
+            LogViewAction::SelectPrevious => self.move_selection(true),
+            LogViewAction::SelectNext => self.move_selection(false),
+            LogViewAction::Activate => self.activate(),
+            LogViewAction::Expand => self.expand_selected(true),
+            LogViewAction::Collapse => self.expand_selected(false),
+            LogViewAction::ToggleCollapseAll => {
+                self.set_collapse_all(!self.collapse_all);
+                EventResult::Consumed(None)
+            }
+            LogViewAction::ToggleBookmark if self.bookmarks => {
+                self.toggle_bookmark();
+                EventResult::Consumed(None)
+            }
+            LogViewAction::NextBookmark | LogViewAction::PreviousBookmark if self.bookmarks => {
+                if self.jump_to_bookmark(action == LogViewAction::NextBookmark) {
+                    EventResult::Consumed(None)
+                } else {
+                    EventResult::Ignored
+                }
+            }
+            LogViewAction::Search => {
+                let query = self.filter.query().to_string();
+                let edits = Arc::clone(&self.filter_edits);
+                EventResult::with_cb(move |siv| {
+                    show_query_filter(siv, &query, Arc::clone(&edits));
+                })
+            }
+            LogViewAction::Filter => {
+                let threads = self
+                    .seen_threads()
+                    .into_iter()
+                    .map(|thread| {
+                        let visible = self.filter.is_thread_visible(&thread);
+                        (thread, visible)
+                    })
+                    .collect::<Vec<_>>();
+                let edits = Arc::clone(&self.filter_edits);
+                EventResult::with_cb(move |siv| {
+                    show_thread_filter(siv, &threads, Arc::clone(&edits));
+                })
+            }
+            LogViewAction::Clear => {
+                self.store.clear();
+                self.selected = None;
+                self.expanded.clear();
+                EventResult::Consumed(None)
+            }
+            LogViewAction::Export => {
+                let path = self.export_path.clone();
+                let message = match self.export_file() {
+                    Ok(()) => format!("Records written to {}", path.display()),
+                    Err(err) => format!("Failed to write {}: {}", path.display(), err),
+                };
+                EventResult::with_cb(move |siv| siv.add_layer(Dialog::info(message.as_str())))
+            }
+            _ => EventResult::Ignored,
+        }
+    }
+
+    fn export_file(&self) -> io::Result<()> {
+        if let Some(parent) = self.export_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        self.export(&mut File::create(&self.export_path)?)
+    }
+
+    /// Apply the changes made to the filter by the dialogs of this view.
+    fn apply_filter_edits(&mut self) {
+        let edits =
+            std::mem::take(&mut *self.filter_edits.lock().unwrap_or_else(|e| e.into_inner()));
+        for edit in edits {
+            edit(&mut self.filter);
+        }
+    }
+
+    fn header_rows(&self) -> usize {
+        match &self.columns {
+            Some(layout) if layout.has_header() => 1,
+            _ => 0,
+        }
+    }
+
+    /// Iterate over the entries passing the filter.
+    fn entries<'a>(&self, logs: &'a LogBuffer) -> impl Iterator<Item = &'a LogEntry> + Clone {
+        let filter = self.filter.clone();
+        logs.iter().filter(move |entry| filter.matches(entry))
+    }
+
+    /// Iterate over the entries which fit into `height` rows, together with
+    /// the row they start at.
+    fn visible<'a>(
+        &'a self,
+        logs: &'a LogBuffer,
+        height: usize,
+    ) -> impl Iterator<Item = (usize, &'a LogEntry)> {
+        let header = self.header_rows();
+        let entries = self.entries(logs);
+
+        // Only print the last logs, so skip what doesn't fit
+        let skipped = entries
+            .clone()
+            .count()
+            .saturating_sub(height.saturating_sub(header));
+
+        entries.skip(skipped).scan(header, move |y, entry| {
+            let row = *y;
+            *y += self.rows(entry);
+            Some((row, entry))
+        })
+    }
+
+    fn move_selection(&mut self, up: bool) -> EventResult {
+        let logs = self.store.lock();
+        let entries = self.entries(&logs).collect::<Vec<_>>();
+        let current = self
+            .selected
+            .and_then(|seq| entries.iter().position(|entry| entry.seq == seq));
+        let next = match current {
+            None => entries.len().checked_sub(1),
+            Some(index) if up => index.checked_sub(1),
+            Some(index) => Some(index + 1),
+        };
+        match next.and_then(|index| entries.get(index)) {
+            Some(entry) => {
+                self.selected = Some(entry.seq);
+                EventResult::Consumed(None)
+            }
+            None => EventResult::Ignored,
+        }
+    }
+
+    fn activate(&self) -> EventResult {
+        let logs = self.store.lock();
+        let entry = self
+            .selected
+            .and_then(|seq| logs.position(seq))
+            .and_then(|index| logs.get(index));
+        match (entry, &self.on_activate) {
+            (Some(entry), Some(cb)) => {
+                let entry = entry.clone();
+                let cb = Arc::clone(cb);
+                EventResult::with_cb(move |siv| cb(siv, &entry))
+            }
+            _ => EventResult::Ignored,
+        }
+    }
+
+    fn click(&mut self, position: Vec2) -> EventResult {
+        let logs = self.store.lock();
+        let (row, entry) = match self
+            .visible(&logs, self.last_size.y)
+            .take_while(|(row, _)| *row <= position.y)
+            .last()
+        {
+            Some((row, entry)) if position.y < row + self.rows(entry) => (row, entry),
+            _ => return EventResult::Ignored,
+        };
+        let seq = entry.seq;
+
+        // find the span holding the source location of the entry
+        let (msg, message) = (self.renderer(&logs, None).1)(entry);
+        let location = match &self.columns {
+            None => message.checked_sub(1),
+            Some(layout) => layout
+                .widths(self.entries(&logs))
+                .iter()
+                .position(|(column, _)| *column == Column::Location)
+                .map(|index| 2 * index + usize::from(self.bookmarks)),
+        };
+        let on_location = location.is_some_and(|index| {
+            let start = msg
+                .spans()
+                .take(index)
+                .map(|span| span.width)
+                .sum::<usize>();
+            let width = msg.spans().nth(index).map_or(0, |span| span.width);
+            position.y == row && (start..start + width).contains(&position.x)
+        });
+
+        drop(logs);
+        self.selected = Some(seq);
+        if on_location {
+            self.activate()
+        } else {
+            EventResult::Consumed(None)
+        }
+    }
+
+    /// Render the header row and a closure rendering single entries, taking
+    /// the column mode into account. The closure returns the rendered entry
+    /// together with the index of the first span of the message.
+    fn renderer<'a>(
+        &'a self,
+        logs: &'a LogBuffer,
+        theme: Option<&Theme>,
+    ) -> (
+        Option<StyledString>,
+        impl Fn(&LogEntry) -> (StyledString, usize) + 'a,
+    ) {
+        let colors = self.color_by.map(|by| NameColors::new(by, theme));
+        let widths = self
+            .columns
+            .as_ref()
+            .map(|layout| (layout.has_header(), layout.widths(self.entries(logs))));
+        let gutter = self.bookmarks;
+        let header = match &widths {
+            Some((true, widths)) if gutter => {
+                let mut line = StyledString::plain(GUTTER_EMPTY);
+                line.append(ColumnLayout::header_row(widths));
+                Some(line)
+            }
+            Some((true, widths)) => Some(ColumnLayout::header_row(widths)),
+            _ => None,
+        };
+        let render = move |entry: &LogEntry| {
+            let mut line = if !gutter {
+                StyledString::new()
+            } else if logs.is_bookmarked(entry.seq) {
+                StyledString::styled(GUTTER_BOOKMARK, Effect::Bold)
+            } else {
+                StyledString::plain(GUTTER_EMPTY)
+            };
+            line.append(match &widths {
+                Some((_, widths)) => ColumnLayout::row(entry, widths, colors.as_ref()),
+                None => styled_entry(entry, colors.as_ref()),
+            });
+            let message = line.spans_raw().len();
+            line.append(self.styled_message(entry));
+            (line, message)
+        };
+        (header, render)
+    }
+
+    /// Render the message of `entry`, followed by its key-value pairs.
+    /// Folded multi-line messages are cut after their first line.
+    fn styled_message(&self, entry: &LogEntry) -> StyledString {
+        let color = level_color(entry.level);
+        // JSON is only shown as a tree if the entry was expanded explicitly
+        let tree = self.expanded.get(&entry.seq) == Some(&true);
+
+        let mut line = StyledString::new();
+        match entry.json_start.filter(|_| self.json) {
+            Some(start) => {
+                let (text, payload) = entry.message.split_at(start);
+                if !text.is_empty() {
+                    line.append_styled(text, color);
+                }
+                json::append_json(&mut line, payload, tree, color.into());
+            }
+            None => line.append_styled(&entry.message, color),
+        }
+        let mut line = highlight::highlight(line, &self.highlights);
+        append_fields(&mut line, entry);
+
+        let rows = line.source().split('\n').count();
+        if rows == 1 || !self.folded(entry) {
+            return line;
+        }
+        let mut folded = StyledString::new();
+        for span in line.spans() {
+            match span.content.split_once('\n') {
+                Some((head, _)) => {
+                    folded.append_styled(head, *span.attr);
+                    break;
+                }
+                None => folded.append_styled(span.content, *span.attr),
+            }
+        }
+        folded.append_styled(format!(" [+{} lines]", rows - 1), Effect::Dim);
+        folded
+    }
+
+    /// The number of rows `entry` spans.
+    fn rows(&self, entry: &LogEntry) -> usize {
+        // JSON is only shown as a tree if the entry was expanded explicitly
+        if !has_newlines(entry) && self.expanded.get(&entry.seq) != Some(&true) {
+            return 1;
+        }
+        self.styled_message(entry).source().split('\n').count()
+    }
+
+    /// Print a single styled line, where the log message starts at the span
+    /// with index `message` and may span multiple rows.
+    fn print_styled(
+        &self,
+        printer: &Printer<'_, '_>,
+        mut y: usize,
+        msg: &StyledString,
+        message: usize,
+    ) {
+        let mut x = 0;
+
+        for span in msg.spans().take(message) {
+            printer.with_style(*span.attr, |printer| {
+                printer.print((x, y), span.content);
+            });
+            x += span.width;
+        }
+
+        // x is kept for continuation lines ⇒ multiline messages look like this:
+        // DEBUG <src/main.rs:47> first line
+        //                        second line
+        let start = if self.indent { x } else { 0 };
+        for span in msg.spans().skip(message) {
+            for (index, part) in span.content.split('\n').enumerate() {
+                if index > 0 {
+                    y += 1;
+                    x = start;
+                }
+                printer.with_style(*span.attr, |printer| {
+                    printer.print((x, y), part);
+                });
+                x += part.width();
+            }
+        }
+    }
+}
+
+impl Default for FlexiLoggerView {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -231,67 +1027,106 @@ impl Indentable for FlexiLoggerView {
 
 impl View for FlexiLoggerView {
     fn draw(&self, printer: &Printer<'_, '_>) {
-        let logs = LOGS.lock().unwrap();
+        let logs = self.store.lock();
+        let (header, render) = self.renderer(&logs, Some(printer.theme));
 
-        // Only print the last logs, so skip what doesn't fit
-        let skipped = logs.len().saturating_sub(printer.size.y);
-
-        let mut y = 0;
-        for msg in logs.iter().skip(skipped) {
-            let mut x = 0;
-
-            // Assume the log message is the last styled span
-            let log_msg_index = msg.spans_raw().len() - 1;
+        if let Some(header) = header {
+            self.print_styled(printer, 0, &header, header.spans_raw().len());
+        }
 
-            for span in msg.spans().take(log_msg_index) {
-                printer.with_style(*span.attr, |printer| {
-                    printer.print((x, y), span.content);
+        for (y, entry) in self.visible(&logs, printer.size.y) {
+            let (msg, message) = render(entry);
+            if printer.focused && self.selected == Some(entry.seq) {
+                printer.with_effect(Effect::Reverse, |printer| {
+                    self.print_styled(printer, y, &msg, message);
                 });
-                x += span.width;
+            } else {
+                self.print_styled(printer, y, &msg, message);
             }
+        }
+    }
 
-            let log_msg = msg.spans().skip(log_msg_index).next().unwrap();
-            for part in log_msg.content.split('\n') {
-                printer.with_style(*log_msg.attr, |printer| {
-                    printer.print((x, y), part);
-                });
-                y += 1;
-                if !self.indent {
-                    x = 0;
-                }
-                // x is not modified ⇒ multiline messages look like this:
-                // DEBUG <src/main.rs:47> first line
-                //                        second line
-            }
+    fn layout(&mut self, size: Vec2) {
+        self.apply_filter_edits();
+        self.last_size = size;
+    }
+
+    fn take_focus(&mut self, _source: Direction) -> Result<EventResult, CannotFocus> {
+        if self.is_interactive() {
+            Ok(EventResult::Consumed(None))
+        } else {
+            Err(CannotFocus)
+        }
+    }
+
+    fn on_event(&mut self, event: Event) -> EventResult {
+        if !self.is_interactive() {
+            return EventResult::Ignored;
+        }
+        self.apply_filter_edits();
+
+        if let Some(action) = self.keymap.action(&event) {
+            return self.perform(action);
+        }
+        match event {
+            Event::Mouse {
+                offset,
+                position,
+                event: MouseEvent::Press(MouseButton::Left),
+            } => match position.checked_sub(offset) {
+                Some(position) => self.click(position),
+                None => EventResult::Ignored,
+            },
+            _ => EventResult::Ignored,
         }
     }
 
+    fn important_area(&self, size: Vec2) -> Rect {
+        // do not scroll back to the selection before a scroll action
+        if self.scroll.is_some() {
+            return Rect::from_size((0, 0), size);
+        }
+        let logs = self.store.lock();
+        let selected = self
+            .visible(&logs, size.y)
+            .find(|(_, entry)| Some(entry.seq) == self.selected)
+            .map(|(row, entry)| Rect::from_size((0, row), (size.x, self.rows(entry))));
+        selected.unwrap_or_else(|| Rect::from_size((0, 0), size))
+    }
+
     fn required_size(&mut self, constraint: Vec2) -> Vec2 {
-        let logs = LOGS.lock().unwrap();
+        self.apply_filter_edits();
+        let logs = self.store.lock();
+        let (header, render) = self.renderer(&logs, None);
 
         // The longest line sets the width
-        let w = logs
-            .iter()
-            .map(|msg| {
-                msg.spans()
-                    .map(|x|
-                    // if the log message contains more than one line,
-                    // only the longest line should be considered
-                    // (definitely not the total content.len())
-                    x.content.split('\n').map(|x| x.width()).max().unwrap())
-                    .sum::<usize>()
-            })
+        let w = header
+            .map(|header| header.width())
+            .into_iter()
+            .chain(self.entries(&logs).map(|entry| {
+                let (msg, message) = render(entry);
+                let prefix = msg
+                    .spans()
+                    .take(message)
+                    .map(|span| span.width)
+                    .sum::<usize>();
+                // if the log message contains more than one line,
+                // only the longest line should be considered
+                // (definitely not the total content.len())
+                let text = msg
+                    .spans()
+                    .skip(message)
+                    .map(|span| span.content)
+                    .collect::<String>();
+                prefix + text.split('\n').map(|x| x.width()).max().unwrap()
+            }))
             .max()
             .unwrap_or(1);
-        let h = logs
-            .iter()
-            .map(|msg| {
-                msg.spans()
-                    .last()
-                    .map(|x| x.content.split('\n').count())
-                    .unwrap()
-            })
+        let h = self
+            .entries(&logs)
+            .map(|entry| self.rows(entry))
             .sum::<usize>();
+        let h = h + self.header_rows();
         let w = std::cmp::max(w, constraint.x);
         let h = std::cmp::max(h, constraint.y);
 
@@ -299,11 +1134,97 @@ impl View for FlexiLoggerView {
     }
 }
 
+/// Perform the scroll action recorded by the `FlexiLoggerView` inside `view`.
+/// This is the `on_scroll_inner` callback of the `ScrollView`, which runs after
+/// the log view consumed an event.
+pub(crate) fn perform_scroll(view: &mut ScrollView<FlexiLoggerView>, _: Rect) -> EventResult {
+    // scrolling below runs the callback again, without an action
+    let action = match view.get_inner_mut().scroll.take() {
+        Some(action) => action,
+        None => return EventResult::Ignored,
+    };
+    if action == LogViewAction::Follow {
+        view.get_inner_mut().selected = None;
+        return view.set_scroll_strategy(ScrollStrategy::StickToBottom);
+    }
+
+    // scrolling pauses following new records
+    let paused = view.set_scroll_strategy(ScrollStrategy::KeepRow);
+    let viewport = view.content_viewport();
+    let top = viewport.top();
+    let page = viewport.height().saturating_sub(1).max(1);
+    let y = match action {
+        LogViewAction::ScrollUp => top.saturating_sub(1),
+        LogViewAction::ScrollDown => top + 1,
+        LogViewAction::PageUp => top.saturating_sub(page),
+        LogViewAction::PageDown => top + page,
+        _ => 0,
+    };
+    let mut result = paused.and(view.set_offset((viewport.left(), y)));
+
+    // until the bottom is reached again
+    if view.is_at_bottom() {
+        result = result.and(view.set_scroll_strategy(ScrollStrategy::StickToBottom));
+    }
+    result
+}
+
 /// The `flexi_logger` `LogWriter` implementation for the `FlexiLoggerView`.
 ///
 /// Use the `cursive_flexi_logger` function to create an instance of this struct.
 pub struct CursiveLogWriter {
     sink: CbSink,
+    store: LogStore,
+    queue: Option<IngestQueue>,
+    limiter: Option<RateLimiter>,
+}
+
+/// The queue of a non-blocking `CursiveLogWriter`.
+struct IngestQueue {
+    sender: SyncSender<LogEntry>,
+    progress: Arc<IngestProgress>,
+}
+
+/// Counts the records handed to the ingestion thread and the ones it moved
+/// into the store, so `flush` can wait until the queue is drained.
+#[derive(Default)]
+struct IngestProgress {
+    queued: AtomicU64,
+    stored: AtomicU64,
+    /// Only taken by `wait_drained`, so writers never wait for it.
+    lock: Mutex<()>,
+    drained: Condvar,
+}
+
+impl IngestProgress {
+    fn queued(&self) {
+        self.queued.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn stored(&self, records: u64) {
+        self.stored.fetch_add(records, Ordering::SeqCst);
+        self.drained.notify_all();
+    }
+
+    /// Wait until the records queued so far are stored, or `timeout` passed.
+    fn wait_drained(&self, timeout: Duration) {
+        let queued = self.queued.load(Ordering::SeqCst);
+        let deadline = Instant::now() + timeout;
+        let mut lock = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        while self.stored.load(Ordering::SeqCst) < queued {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                break;
+            }
+            // `stored` notifies without the lock, so a notification may come
+            // before this thread waits, check again after a while
+            lock = self
+                .drained
+                .wait_timeout(lock, left.min(DRAIN_POLL_INTERVAL))
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+    }
 }
 
 /// Creates a new `LogWriter` instance for the `FlexiLoggerView`. Use this to
@@ -343,38 +1264,206 @@ pub struct CursiveLogWriter {
 pub fn cursive_flexi_logger(siv: &Cursive) -> Box<CursiveLogWriter> {
     Box::new(CursiveLogWriter {
         sink: siv.cb_sink().clone(),
+        store: LogStore::global(),
+        queue: None,
+        limiter: None,
     })
 }
 
+/// Remove all records from the global `LogStore`, see `LogStore::clear`.
+pub fn clear_logs() {
+    LogStore::global().clear();
+}
+
+/// Returns a copy of all records in the global `LogStore`, oldest first. Use
+/// `LogStore::range` or `LogStore::seq_range` to read only some of them.
+pub fn log_snapshot() -> Vec<LogEntry> {
+    LogStore::global().snapshot()
+}
+
+impl CursiveLogWriter {
+    /// Make `write` only enqueue records, so logging threads never wait for
+    /// the UI, which holds the store while rendering. A background thread
+    /// moves the queued records into the store and triggers a redraw.
+    ///
+    /// The queue holds at most `capacity` records. When it is full, new
+    /// records are dropped and counted in `LogStore::dropped_records`.
+    /// `flush`, e.g. through `log::logger().flush()`, waits up to a second
+    /// until the queued records are stored. `LogDump` and `PanicHook` flush
+    /// before they read the store.
+    ///
+    /// # Register a non-blocking cursive log writer
+    ///
+    /// ```rust
+    /// use cursive::{Cursive, CursiveExt};
+    /// use flexi_logger::Logger;
+    ///
+    /// fn main() {
+    ///     let mut siv = Cursive::default();
+    ///
+    ///     Logger::try_with_env_or_str("trace")
+    ///         .expect("Could not create Logger from environment :(")
+    ///         .log_to_writer(
+    ///             cursive_flexi_logger_view::cursive_flexi_logger(&siv)
+    ///                 .non_blocking(4096)
+    ///                 .expect("failed to spawn log ingestion thread"),
+    ///         )
+    ///         .start()
+    ///         .expect("failed to initialize logger!");
+    /// }
+    /// ```
+    pub fn non_blocking(mut self: Box<Self>, capacity: usize) -> std::io::Result<Box<Self>> {
+        let (sender, receiver) = mpsc::sync_channel(capacity);
+        let sink = self.sink.clone();
+        let store = self.store.clone();
+        let progress = Arc::new(IngestProgress::default());
+        thread::Builder::new()
+            .name("flexi-log-ingest".to_string())
+            .spawn({
+                let progress = Arc::clone(&progress);
+                move || ingest(receiver, &store, &sink, &progress)
+            })?;
+        self.queue = Some(IngestQueue { sender, progress });
+        Ok(self)
+    }
+
+    /// Push the records to `store` instead of the global store. Call this
+    /// before `non_blocking`, as the ingestion thread keeps the store it was
+    /// spawned with.
+    pub fn store(mut self: Box<Self>, store: LogStore) -> Box<Self> {
+        self.store = store;
+        self
+    }
+
+    /// Limit the rate of records per target, see `RateLimits`.
+    pub fn rate_limits(mut self: Box<Self>, limits: RateLimits) -> Box<Self> {
+        self.limiter = Some(RateLimiter::new(limits));
+        self
+    }
+
+    /// Hand `entry` to the ingestion thread, or push it to the store.
+    fn submit(&self, entry: LogEntry) -> std::io::Result<()> {
+        match &self.queue {
+            Some(queue) => match queue.sender.try_send(entry) {
+                Ok(()) => {
+                    queue.progress.queued();
+                    Ok(())
+                }
+                Err(TrySendError::Full(_)) => {
+                    self.store.count_dropped();
+                    Ok(())
+                }
+                Err(TrySendError::Disconnected(_)) => Err(std::io::Error::new(
+                    std::io::ErrorKind::BrokenPipe,
+                    "log ingestion thread is gone!",
+                )),
+            },
+            None => {
+                self.store.push(entry);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Move queued records into `store` until all writers are gone. Records which
+/// arrived in the meantime are pushed at once, followed by a single redraw.
+fn ingest(
+    receiver: Receiver<LogEntry>,
+    store: &LogStore,
+    sink: &CbSink,
+    progress: &IngestProgress,
+) {
+    let mut redraw = true;
+    while let Ok(entry) = receiver.recv() {
+        let entries: Vec<_> = std::iter::once(entry).chain(receiver.try_iter()).collect();
+        let records = entries.len() as u64;
+        store.extend(entries);
+        progress.stored(records);
+        // keep storing records after cursive is gone, e.g. for a `LogDump`
+        redraw = redraw && sink.send(Box::new(|_siv| {})).is_ok();
+    }
+}
+
 use time::{format_description::FormatItem, macros::format_description};
 
-const FORMAT: &[FormatItem<'static>] = format_description!("%T%.3f");
+const FORMAT: &[FormatItem<'static>] =
+    format_description!("[hour]:[minute]:[second].[subsecond digits:3]");
+
+fn level_color(level: Level) -> Color {
+    Color::Dark(match level {
+        Level::Trace => BaseColor::Green,
+        Level::Debug => BaseColor::Cyan,
+        Level::Info => BaseColor::Blue,
+        Level::Warn => BaseColor::Yellow,
+        Level::Error => BaseColor::Red,
+    })
+}
+
+/// Render everything of a log entry in front of its message.
+fn styled_entry(entry: &LogEntry, colors: Option<&NameColors>) -> StyledString {
+    let color = level_color(entry.level);
+
+    let mut line = StyledString::new();
+    line.append_styled(entry.time.format(FORMAT).unwrap_or_default(), color);
+    if let Some(source) = &entry.source {
+        line.append_plain(format!(" ({})", source));
+    }
+    line.append_plain(" [");
+    match colors.and_then(|colors| colors.thread(&entry.thread)) {
+        Some(thread_color) => line.append_styled(&entry.thread, thread_color),
+        None => line.append_plain(&entry.thread),
+    }
+    line.append_plain("] ");
+    line.append_styled(entry.level.to_string(), color);
+    line.append_plain(format!(
+        " <{}:{}> ",
+        entry.file.as_deref().unwrap_or("(unnamed)"),
+        entry.line.unwrap_or(0),
+    ));
+    line
+}
+
+/// Append the key-value pairs of `entry` as a dimmed span after the message.
+fn append_fields(line: &mut StyledString, entry: &LogEntry) {
+    if entry.fields.is_empty() {
+        return;
+    }
+    let fields = entry
+        .fields
+        .iter()
+        .map(|(key, value)| format!(" {}={}", key, value))
+        .collect::<String>();
+    line.append_styled(fields, Effect::Dim);
+}
+
+/// Returns whether the message or the key-value pairs of `entry` span
+/// multiple lines.
+fn has_newlines(entry: &LogEntry) -> bool {
+    entry.message.contains('\n')
+        || entry
+            .fields
+            .iter()
+            .any(|(key, value)| key.contains('\n') || value.contains('\n'))
+}
 
 impl LogWriter for CursiveLogWriter {
     fn write(&self, now: &mut DeferredNow, record: &Record) -> std::io::Result<()> {
-        let color = Color::Dark(match record.level() {
-            Level::Trace => BaseColor::Green,
-            Level::Debug => BaseColor::Cyan,
-            Level::Info => BaseColor::Blue,
-            Level::Warn => BaseColor::Yellow,
-            Level::Error => BaseColor::Red,
-        });
+        let mut passed = true;
+        if let Some(limiter) = &self.limiter {
+            for summary in limiter.summaries(now) {
+                self.submit(summary)?;
+            }
+            passed = limiter.check(record.target(), record.level());
+        }
+        if passed {
+            self.submit(LogEntry::from_record(now, record))?;
+        }
 
-        let mut line = StyledString::new();
-        line.append_styled(format!("{}", now.format(FORMAT)), color);
-        line.append_plain(format!(
-            " [{}] ",
-            thread::current().name().unwrap_or("(unnamed)"),
-        ));
-        line.append_styled(format!("{}", record.level()), color);
-        line.append_plain(format!(
-            " <{}:{}> ",
-            record.file().unwrap_or("(unnamed)"),
-            record.line().unwrap_or(0),
-        ));
-        line.append_styled(format!("{}", &record.args()), color);
-
-        LOGS.lock().unwrap().push_back(line);
+        // the ingestion thread triggers the redraw
+        if self.queue.is_some() {
+            return Ok(());
+        }
         self.sink.send(Box::new(|_siv| {})).map_err(|_| {
             std::io::Error::new(
                 std::io::ErrorKind::BrokenPipe,
@@ -384,7 +1473,11 @@ impl LogWriter for CursiveLogWriter {
     }
 
     fn flush(&self) -> std::io::Result<()> {
-        // we are not buffering
+        // queued records are moved into the store by the ingestion thread,
+        // wait for it to catch up
+        if let Some(queue) = &self.queue {
+            queue.progress.wait_drained(FLUSH_TIMEOUT);
+        }
         Ok(())
     }
 
@@ -397,6 +1490,10 @@ impl LogWriter for CursiveLogWriter {
 ///
 /// This is analog to [`Cursive::show_debug_console`](/cursive/latest/cursive/struct.Cursive.html#method.show_debug_console).
 ///
+/// The records of this console can only be scrolled, its view does not
+/// handle the keys of a `LogViewKeymap`. Use a `DebugConsole` to select,
+/// bookmark or clear records.
+///
 /// # Add binding to show flexi_logger debug view
 ///
 /// ```rust
@@ -428,9 +1525,86 @@ impl LogWriter for CursiveLogWriter {
 /// }
 /// ```
 pub fn show_flexi_logger_debug_console(siv: &mut Cursive) {
+    DebugConsole::read_only().show(siv);
+}
+
+/// Call `f` with the `FlexiLoggerView` of the debug console, if it is shown.
+fn with_debug_view<F, R>(siv: &mut Cursive, f: F) -> Option<R>
+where
+    F: FnOnce(&mut FlexiLoggerView) -> R,
+{
+    siv.call_on_name(FLEXI_LOGGER_DEBUG_VIEW_NAME, |panes: &mut ConsolePanes| {
+        f(panes.active_view_mut())
+    })
+}
+
+/// Perform `action` on the `FlexiLoggerView` of the debug console, if it is
+/// shown.
+fn debug_view_action(siv: &mut Cursive, action: LogViewAction) {
+    if let Some(result) = with_debug_view(siv, |view| view.perform(action)) {
+        result.process(siv);
+    }
+}
+
+/// Show a checklist of `threads`, which shows or hides the records of each
+/// thread through `edits`.
+fn show_thread_filter(siv: &mut Cursive, threads: &[(String, bool)], edits: FilterEdits) {
+    let mut list = ListView::new();
+    for (thread, visible) in threads {
+        let name = thread.clone();
+        let edits = Arc::clone(&edits);
+        let checkbox = Checkbox::new()
+            .with_checked(*visible)
+            .on_change(move |_siv, checked| {
+                let name = name.clone();
+                edits
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .push(Box::new(move |filter| {
+                        filter.set_thread_visible(name, checked)
+                    }));
+            });
+        list.add_child(thread, checkbox);
+    }
+
     siv.add_layer(
-        Dialog::around(FlexiLoggerView::scrollable().with_name(FLEXI_LOGGER_DEBUG_VIEW_NAME))
-            .title("Debug console"),
+        Dialog::around(list.scrollable())
+            .title("Threads")
+            .dismiss_button("Close"),
+    );
+}
+
+/// Show an input for a filter query, which is applied through `edits`. See
+/// `LogFilter::set_query` for the syntax.
+fn show_query_filter(siv: &mut Cursive, query: &str, edits: FilterEdits) {
+    let apply = move |siv: &mut Cursive, query: &str| {
+        let query = query.to_string();
+        edits
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Box::new(move |filter| filter.set_query(query)));
+        siv.pop_layer();
+    };
+    let submit = apply.clone();
+
+    siv.add_layer(
+        Dialog::around(
+            EditView::new()
+                .content(query)
+                .on_submit(submit)
+                .with_name(FLEXI_LOGGER_DEBUG_QUERY_NAME)
+                .min_width(40),
+        )
+        .title("Filter")
+        .button("Apply", move |siv| {
+            let query = siv
+                .call_on_name(FLEXI_LOGGER_DEBUG_QUERY_NAME, |edit: &mut EditView| {
+                    edit.get_content()
+                })
+                .unwrap_or_default();
+            apply(siv, &query);
+        })
+        .dismiss_button("Cancel"),
     );
 }
 
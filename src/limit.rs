@@ -0,0 +1,331 @@
+use flexi_logger::{DeferredNow, Level};
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::filter::is_in_module;
+use crate::store::thread_name;
+use crate::LogEntry;
+
+/// How often records of a limited target are summarized.
+const SUMMARY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Token bucket rate limits for the records of a `CursiveLogWriter`.
+///
+/// Each rule selects records by target or level and limits them to a number
+/// of records per second, allowing short bursts. Every target is limited on
+/// its own. Records exceeding the limit are dropped and summarized in records
+/// like `suppressed 1234 messages from hyper::proto in last 1s`. A summary is
+/// written with the next record after the second has passed.
+///
+/// The first matching rule applies, records not matching any rule are not
+/// limited.
+///
+/// # Limit a chatty dependency
+///
+/// ```rust
+/// use cursive::Cursive;
+/// use cursive_flexi_logger_view::RateLimits;
+/// use flexi_logger::{Level, Logger};
+///
+/// fn main() {
+///     let siv = Cursive::default();
+///
+///     Logger::try_with_env_or_str("trace")
+///         .expect("Could not create Logger from environment :(")
+///         .log_to_writer(
+///             cursive_flexi_logger_view::cursive_flexi_logger(&siv).rate_limits(
+///                 RateLimits::new()
+///                     .target("hyper", 20, 50)
+///                     .level(Level::Trace, 100, 100),
+///             ),
+///         )
+///         .start()
+///         .expect("failed to initialize logger!");
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RateLimits {
+    rules: Vec<Rule>,
+}
+
+#[derive(Clone, Debug)]
+struct Rule {
+    selector: Selector,
+    per_second: u32,
+    burst: u32,
+}
+
+#[derive(Clone, Debug)]
+enum Selector {
+    Target(String),
+    Level(Level),
+}
+
+impl RateLimits {
+    /// Create rate limits without any rule.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit the records of `target` and its submodules, e.g. `hyper` also
+    /// limits `hyper::proto`, to `per_second` records per second with bursts
+    /// of up to `burst` records.
+    pub fn target<S: Into<String>>(mut self, target: S, per_second: u32, burst: u32) -> Self {
+        self.rules.push(Rule {
+            selector: Selector::Target(target.into()),
+            per_second,
+            burst,
+        });
+        self
+    }
+
+    /// Limit the records at `level` of each target to `per_second` records
+    /// per second with bursts of up to `burst` records.
+    pub fn level(mut self, level: Level, per_second: u32, burst: u32) -> Self {
+        self.rules.push(Rule {
+            selector: Selector::Level(level),
+            per_second,
+            burst,
+        });
+        self
+    }
+
+    /// Returns the index and the first rule matching a record.
+    fn rule(&self, target: &str, level: Level) -> Option<(usize, &Rule)> {
+        self.rules
+            .iter()
+            .enumerate()
+            .find(|(_, rule)| match &rule.selector {
+                Selector::Target(prefix) => is_in_module(target, prefix),
+                Selector::Level(limited) => *limited == level,
+            })
+    }
+}
+
+/// The state of the token buckets of a `CursiveLogWriter`. There is a bucket
+/// for each rule and target, as rules differ in their rates.
+pub(crate) struct RateLimiter {
+    limits: RateLimits,
+    buckets: Mutex<HashMap<(usize, String), Bucket>>,
+    scanned: Mutex<Instant>,
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled: Instant,
+    suppressed: u64,
+    level: Level,
+    since: Instant,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(limits: RateLimits) -> Self {
+        RateLimiter {
+            limits,
+            buckets: Mutex::new(HashMap::new()),
+            scanned: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Take a token for a record of `target` at `level`. Returns whether the
+    /// record may pass.
+    pub(crate) fn check(&self, target: &str, level: Level) -> bool {
+        self.take(target, level, Instant::now())
+    }
+
+    fn take(&self, target: &str, level: Level, now: Instant) -> bool {
+        let (index, rule) = match self.limits.rule(target, level) {
+            Some(rule) => rule,
+            None => return true,
+        };
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let key = (index, target.to_string());
+        let bucket = buckets.entry(key).or_insert_with(|| Bucket {
+            tokens: f64::from(rule.burst),
+            refilled: now,
+            suppressed: 0,
+            level,
+            since: now,
+        });
+
+        let elapsed = now.duration_since(bucket.refilled).as_secs_f64();
+        bucket.tokens =
+            (bucket.tokens + elapsed * f64::from(rule.per_second)).min(f64::from(rule.burst));
+        bucket.refilled = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return true;
+        }
+
+        if bucket.suppressed == 0 {
+            bucket.since = now;
+            bucket.level = level;
+        }
+        bucket.suppressed += 1;
+        bucket.level = bucket.level.min(level);
+        false
+    }
+
+    /// Take the summaries of the targets which have been suppressed for at
+    /// least a second.
+    pub(crate) fn summaries(&self, now: &mut DeferredNow) -> Vec<LogEntry> {
+        self.summarize(Instant::now(), now)
+    }
+
+    /// Scans the buckets at most once per `SUMMARY_INTERVAL`. Buckets which
+    /// are full again and have nothing to summarize are dropped, so targets
+    /// which stopped logging do not keep their buckets.
+    fn summarize(&self, now: Instant, deferred: &mut DeferredNow) -> Vec<LogEntry> {
+        {
+            let mut scanned = self.scanned.lock().unwrap_or_else(|e| e.into_inner());
+            if now.saturating_duration_since(*scanned) < SUMMARY_INTERVAL {
+                return Vec::new();
+            }
+            *scanned = now;
+        }
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let rules = &self.limits.rules;
+        buckets.retain(|(index, _), bucket| {
+            let rule = &rules[*index];
+            let elapsed = now.saturating_duration_since(bucket.refilled).as_secs_f64();
+            let full =
+                bucket.tokens + elapsed * f64::from(rule.per_second) >= f64::from(rule.burst);
+            bucket.suppressed > 0 || !full
+        });
+        buckets
+            .iter_mut()
+            .filter(|(_, bucket)| {
+                bucket.suppressed > 0
+                    && now.saturating_duration_since(bucket.since) >= SUMMARY_INTERVAL
+            })
+            .map(|((_, target), bucket)| {
+                let seconds = now.saturating_duration_since(bucket.since).as_secs();
+                let message = format!(
+                    "suppressed {} messages from {} in last {}s",
+                    bucket.suppressed, target, seconds
+                );
+                bucket.suppressed = 0;
+                LogEntry {
+                    seq: 0,
+                    time: *deferred.now(),
+                    level: bucket.level,
+                    thread: thread_name(),
+                    target: target.clone(),
+                    file: None,
+                    line: None,
+                    message,
+                    source: None,
+                    fields: Vec::new(),
+                    json_start: None,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buckets_refill_up_to_the_burst() {
+        let limiter = RateLimiter::new(RateLimits::new().target("app", 10, 2));
+        let start = Instant::now();
+        assert!(limiter.take("app", Level::Info, start));
+        assert!(limiter.take("app::net", Level::Info, start));
+        assert!(limiter.take("app", Level::Info, start));
+        assert!(!limiter.take("app", Level::Info, start));
+
+        // a tenth of a second refills a single token
+        let later = start + Duration::from_millis(100);
+        assert!(limiter.take("app", Level::Info, later));
+        assert!(!limiter.take("app", Level::Info, later));
+
+        let much_later = start + Duration::from_secs(10);
+        assert!(limiter.take("app", Level::Info, much_later));
+        assert!(limiter.take("app", Level::Info, much_later));
+        assert!(!limiter.take("app", Level::Info, much_later));
+
+        assert!(limiter.take("other", Level::Info, start));
+    }
+
+    #[test]
+    fn rules_do_not_share_buckets() {
+        let limiter = RateLimiter::new(RateLimits::new().level(Level::Trace, 1, 1).level(
+            Level::Debug,
+            1000,
+            1000,
+        ));
+        let now = Instant::now();
+        assert!(limiter.take("a", Level::Trace, now));
+        assert!(!limiter.take("a", Level::Trace, now));
+        for _ in 0..5 {
+            assert!(limiter.take("a", Level::Debug, now));
+        }
+    }
+
+    #[test]
+    fn summaries_count_suppressed_records() {
+        let limiter = RateLimiter::new(RateLimits::new().target("app", 1, 1));
+        let now = Instant::now();
+        assert!(limiter.take("app", Level::Info, now));
+        assert!(!limiter.take("app", Level::Debug, now));
+        assert!(!limiter.take("app", Level::Warn, now));
+
+        // nothing is summarized before a second has passed
+        let mut deferred = DeferredNow::new();
+        assert!(limiter.summarize(now, &mut deferred).is_empty());
+
+        let later = now + SUMMARY_INTERVAL;
+        let summaries = limiter.summarize(later, &mut deferred);
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].target, "app");
+        assert_eq!(summaries[0].level, Level::Warn);
+        assert_eq!(
+            summaries[0].message,
+            "suppressed 2 messages from app in last 1s"
+        );
+        let much_later = later + SUMMARY_INTERVAL;
+        assert!(limiter.summarize(much_later, &mut deferred).is_empty());
+    }
+
+    #[test]
+    fn buckets_are_scanned_once_per_interval() {
+        let limiter = RateLimiter::new(RateLimits::new().target("app", 1, 1));
+        let now = Instant::now() + SUMMARY_INTERVAL;
+        assert!(limiter.take("app", Level::Info, now));
+        assert!(!limiter.take("app", Level::Info, now));
+
+        let mut deferred = DeferredNow::new();
+        let later = now + SUMMARY_INTERVAL;
+        assert!(limiter.summarize(now, &mut deferred).is_empty());
+        // the scan at `now` delays the next one
+        assert!(limiter
+            .summarize(later - Duration::from_millis(1), &mut deferred)
+            .is_empty());
+        assert_eq!(limiter.summarize(later, &mut deferred).len(), 1);
+    }
+
+    #[test]
+    fn idle_buckets_are_dropped() {
+        let limiter = RateLimiter::new(RateLimits::new().target("app", 1, 2));
+        let now = Instant::now() + SUMMARY_INTERVAL;
+        assert!(limiter.take("app::a", Level::Info, now));
+        assert!(limiter.take("app::b", Level::Info, now));
+        assert!(limiter.take("app::b", Level::Info, now));
+        assert!(!limiter.take("app::b", Level::Info, now));
+
+        // `app::a` is full again, `app::b` still has a record to summarize
+        let mut deferred = DeferredNow::new();
+        let later = now + SUMMARY_INTERVAL;
+        assert_eq!(limiter.summarize(later, &mut deferred).len(), 1);
+        assert_eq!(limiter.buckets.lock().unwrap().len(), 1);
+
+        let much_later = later + 2 * SUMMARY_INTERVAL;
+        assert!(limiter.summarize(much_later, &mut deferred).is_empty());
+        assert!(limiter.buckets.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,194 @@
+use flexi_logger::{DeferredNow, Level};
+
+use std::backtrace::Backtrace;
+use std::fs::File;
+use std::io::{self, IsTerminal, Write};
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::thread::{self, ThreadId};
+
+use crate::dump::write_entries;
+use crate::store::{thread_name, LogBuffer};
+use crate::terminal::{TerminalMode, DISABLE_MOUSE, LEAVE_SCREEN, RESET_STYLE};
+use crate::{LogEntry, LogStore};
+
+/// A panic hook which keeps the log records of a crashing TUI.
+///
+/// When a panic occurs, the hook records it as an `ERROR` entry including a
+/// backtrace. If the UI thread panics, the hook restores the terminal, so the
+/// panic message does not get lost in cursive's alternate screen.
+/// Afterwards the previously installed panic hook runs and the last records of
+/// the store are printed to stderr, or the whole store is written to a crash
+/// file. Panics of other threads are only recorded and passed on to the
+/// previous hook.
+///
+/// To restore the terminal, the hook leaves the alternate screen, shows the
+/// cursor, stops mouse reporting, resets the colors and, on unix platforms,
+/// switches the terminal back to the mode it had when the hook was installed.
+/// This also works with `panic = "abort"`, so install the hook before cursive
+/// switches the terminal into raw mode.
+///
+/// If the UI thread panics while it holds the store, e.g. in the `draw` of a
+/// `FlexiLoggerView`, the records are dumped as soon as unwinding releases
+/// the store. With `panic = "abort"` they cannot be dumped in that case.
+///
+/// # Write a crash file when the application panics
+///
+/// ```rust
+/// use cursive_flexi_logger_view::PanicHook;
+///
+/// PanicHook::new().crash_file("logs/crash.log").install();
+/// ```
+pub struct PanicHook {
+    store: LogStore,
+    tail: usize,
+    crash_file: Option<PathBuf>,
+    ui_thread: Option<ThreadId>,
+}
+
+impl PanicHook {
+    /// Create a panic hook for the global `LogStore`, which prints the last
+    /// 50 records to stderr.
+    pub fn new() -> Self {
+        PanicHook {
+            store: LogStore::global(),
+            tail: 50,
+            crash_file: None,
+            ui_thread: None,
+        }
+    }
+
+    /// Record the panic in `store` and dump its records instead of the ones
+    /// of the global store.
+    pub fn store(mut self, store: LogStore) -> Self {
+        self.store = store;
+        self
+    }
+
+    /// Set how many of the last records are printed to stderr.
+    pub fn tail(mut self, records: usize) -> Self {
+        self.tail = records;
+        self
+    }
+
+    /// Write all records to the file at `path` instead of printing the last
+    /// ones to stderr. If the file cannot be written, the records are printed.
+    pub fn crash_file<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.crash_file = Some(path.into());
+        self
+    }
+
+    /// Set the thread which runs cursive, e.g. if it does not run on the
+    /// thread installing the hook. Only panics of this thread restore the
+    /// terminal and dump the records.
+    pub fn ui_thread(mut self, thread: ThreadId) -> Self {
+        self.ui_thread = Some(thread);
+        self
+    }
+
+    /// Install the hook. The previously installed hook is called by the new
+    /// one.
+    ///
+    /// Unless set with `ui_thread`, the thread calling `install` is taken as
+    /// the UI thread.
+    pub fn install(self) {
+        let ui_thread = self.ui_thread.unwrap_or_else(|| thread::current().id());
+        let mode = TerminalMode::current();
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let message = match info.payload().downcast_ref::<&str>() {
+                Some(message) => message.to_string(),
+                None => match info.payload().downcast_ref::<String>() {
+                    Some(message) => message.clone(),
+                    None => "Box<dyn Any>".to_string(),
+                },
+            };
+            let entry = LogEntry {
+                seq: 0,
+                time: *DeferredNow::new().now(),
+                level: Level::Error,
+                thread: thread_name(),
+                target: "panic".to_string(),
+                file: info.location().map(|location| location.file().to_string()),
+                line: info.location().map(|location| location.line()),
+                message: format!("panicked: {}\n{}", message, Backtrace::force_capture()),
+                source: None,
+                fields: Vec::new(),
+                json_start: None,
+            };
+
+            // a panicking worker thread does not end the application
+            if thread::current().id() != ui_thread {
+                self.store.push(entry);
+                previous(info);
+                return;
+            }
+
+            let mut stderr = io::stderr();
+            // leave the alternate screen, show the cursor, stop mouse
+            // reporting and reset all colors
+            let _ = write!(stderr, "{}{}{}", LEAVE_SCREEN, DISABLE_MOUSE, RESET_STYLE)
+                .and_then(|_| stderr.flush());
+            if let Some(mode) = &mode {
+                mode.restore();
+            }
+            previous(info);
+
+            // move records still queued by a non-blocking writer into the
+            // store, unless this thread holds it and they cannot get there
+            if !self.store.is_held() {
+                log::logger().flush();
+            }
+
+            match self.store.try_lock() {
+                Some(mut logs) => {
+                    logs.push(entry);
+                    dump(&logs, self.tail, self.crash_file.as_deref());
+                }
+                // the panic happened while this thread holds the store, dump
+                // the records once unwinding releases it
+                None if self.store.is_held() => {
+                    self.store.push(entry);
+                    let tail = self.tail;
+                    let crash_file = self.crash_file.clone();
+                    self.store
+                        .on_release(move |logs| dump(logs, tail, crash_file.as_deref()));
+                }
+                // another thread does not release the store, give up
+                None => {}
+            }
+        }));
+    }
+}
+
+/// Write all records of `logs` to `crash_file`, or print the last `tail` ones
+/// to stderr.
+fn dump(logs: &LogBuffer, tail: usize, crash_file: Option<&Path>) {
+    let mut stderr = io::stderr();
+    let written = crash_file.map(|path| {
+        File::create(path)
+            .and_then(|mut file| write_entries(&mut file, logs.iter(), false, "\n"))
+            .map(|_| path)
+    });
+    match written {
+        Some(Ok(path)) => {
+            let _ = writeln!(stderr, "log records written to {}\r", path.display());
+            return;
+        }
+        Some(Err(err)) => {
+            let _ = writeln!(stderr, "failed to write crash file: {}\r", err);
+        }
+        None => {}
+    }
+    let count = logs.iter().count();
+    let tail = logs.iter().skip(count.saturating_sub(tail));
+    let _ = writeln!(stderr, "last log records:\r");
+    let ansi = stderr.is_terminal();
+    let _ = write_entries(&mut stderr, tail, ansi, "\r\n");
+}
+
+impl Default for PanicHook {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,150 @@
+use cursive_core::theme::{Effect, Style};
+use cursive_core::view::View;
+use cursive_core::{Printer, Vec2};
+use flexi_logger::Level;
+
+use crate::{level_color, LogStore};
+
+/// Block characters filling a cell by eighths, from bottom to top.
+const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// The levels in the order they are stacked, from bottom to top.
+const LEVELS: [Level; 5] = [
+    Level::Error,
+    Level::Warn,
+    Level::Info,
+    Level::Debug,
+    Level::Trace,
+];
+
+/// A bar chart of the records pushed into a `LogStore` per second, to spot
+/// bursts and error storms.
+///
+/// Each column shows one second, the newest second is on the right. The bars
+/// stack the levels with the most severe at the bottom, each cell is colored
+/// like the level taking most of it. With a height of one row the chart is a
+/// sparkline, higher charts show the peak rate in their top right corner.
+///
+/// The chart moves on with time, so enable `Cursive::set_autorefresh` to keep
+/// it current while nothing is logged.
+///
+/// # Show the rates of the last minute below the log view
+///
+/// ```rust
+/// use cursive::views::LinearLayout;
+/// use cursive::{Cursive, CursiveExt};
+/// use cursive_flexi_logger_view::{FlexiLoggerView, LogRateView};
+///
+/// fn main() {
+///     let mut siv = Cursive::default();
+///     siv.set_autorefresh(true);
+///
+///     siv.add_fullscreen_layer(
+///         LinearLayout::vertical()
+///             .child(FlexiLoggerView::scrollable())
+///             .child(LogRateView::new().seconds(60).height(4)),
+///     );
+///
+///     // siv.run();
+/// }
+/// ```
+pub struct LogRateView {
+    store: LogStore,
+    seconds: Option<usize>,
+    height: usize,
+}
+
+impl LogRateView {
+    /// Create a chart of the records of the global `LogStore`.
+    pub fn new() -> Self {
+        Self::with_store(LogStore::global())
+    }
+
+    /// Create a chart of the records of `store`.
+    pub fn with_store(store: LogStore) -> Self {
+        LogRateView {
+            store,
+            seconds: None,
+            height: 1,
+        }
+    }
+
+    /// Show the last `seconds` seconds, one column each. By default the chart
+    /// shows as many seconds as fit into its width. The store keeps the rates
+    /// of the last 10 minutes.
+    pub fn seconds(mut self, seconds: usize) -> Self {
+        self.seconds = Some(seconds);
+        self
+    }
+
+    /// Set the height of the chart in rows. Defaults to a single row.
+    pub fn height(mut self, rows: usize) -> Self {
+        self.height = rows.max(1);
+        self
+    }
+}
+
+impl Default for LogRateView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl View for LogRateView {
+    fn draw(&self, printer: &Printer<'_, '_>) {
+        let width = self.seconds.unwrap_or(printer.size.x).min(printer.size.x);
+        let height = printer.size.y;
+        let rates = self.store.record_rates(width);
+        let peak = rates.iter().map(|counts| counts.total()).max().unwrap_or(0);
+        if peak == 0 || height == 0 {
+            return;
+        }
+
+        let eighths = (height * 8) as u64;
+        for (x, counts) in rates.iter().enumerate() {
+            // the top of each level's stack, in eighths of a cell
+            let mut tops = Vec::with_capacity(LEVELS.len());
+            let mut sum = 0;
+            for level in &LEVELS {
+                sum += counts.get(*level);
+                tops.push((sum * eighths).div_ceil(peak) as usize);
+            }
+            let top = tops.last().copied().unwrap_or(0);
+
+            for row in 0..height {
+                let bottom = row * 8;
+                if top <= bottom {
+                    break;
+                }
+                let fill = (top - bottom).min(8);
+                // color the cell like the level covering most of it
+                let mut start = 0;
+                let mut level = (0, Level::Trace);
+                for (candidate, end) in LEVELS.iter().zip(&tops) {
+                    let covered = (*end).min(bottom + fill).saturating_sub(start.max(bottom));
+                    if covered > level.0 {
+                        level = (covered, *candidate);
+                    }
+                    start = *end;
+                }
+                let level = level.1;
+                printer.with_style(level_color(level), |printer| {
+                    printer.print((x, height - 1 - row), &BLOCKS[fill - 1].to_string());
+                });
+            }
+        }
+
+        if height > 1 {
+            let label = format!("{}/s", peak);
+            let x = printer.size.x.saturating_sub(label.len());
+            printer.with_style(Style::from(Effect::Dim), |printer| {
+                printer.print((x, 0), &label);
+            });
+        }
+    }
+
+    fn required_size(&mut self, constraint: Vec2) -> Vec2 {
+        let width = self.seconds.unwrap_or(constraint.x);
+        Vec2::new(width, self.height)
+    }
+}
@@ -0,0 +1,525 @@
+use cursive_core::{CbSink, Cursive};
+use flexi_logger::{writers::LogWriter, DeferredNow, Level, Record};
+use serde_json::{json, Map, Value};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+use std::convert::TryFrom;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+#[cfg(unix)]
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::{LogEntry, LogStore};
+
+/// How long blocking socket operations wait before checking whether the
+/// listener has been stopped.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The longest line read from a connection, longer lines are split into
+/// several records.
+const MAX_LINE_LENGTH: usize = 64 * 1024;
+
+enum Endpoint {
+    Tcp(Vec<SocketAddr>),
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+/// Receives log records from other processes and feeds them into a `LogStore`.
+///
+/// The listener accepts connections on a TCP port or, on unix platforms, on a
+/// unix domain socket. Each connection sends one JSON object per line, as
+/// written by the `RemoteLogWriter`. Lines longer than 64 KiB are split into
+/// several records. Every record is tagged with the peer it originates from:
+/// the `source` field of the record if present, and the address of the
+/// connection otherwise.
+///
+/// # Collect the logs of a child process
+///
+/// ```rust
+/// use cursive::{Cursive, CursiveExt};
+/// use cursive_flexi_logger_view::{FlexiLoggerView, LogListener};
+///
+/// fn main() {
+///     let mut siv = Cursive::default();
+///
+///     let _listener = LogListener::tcp("127.0.0.1:0")
+///         .spawn(&siv)
+///         .expect("failed to listen for log records");
+///
+///     siv.add_layer(FlexiLoggerView::scrollable());
+///     // siv.run();
+/// }
+/// ```
+pub struct LogListener {
+    endpoint: io::Result<Endpoint>,
+    store: LogStore,
+}
+
+impl LogListener {
+    /// Create a new `LogListener` listening on the TCP address `addr`.
+    pub fn tcp<A: ToSocketAddrs>(addr: A) -> Self {
+        let endpoint = addr
+            .to_socket_addrs()
+            .map(|addrs| Endpoint::Tcp(addrs.collect()));
+        LogListener {
+            endpoint,
+            store: LogStore::global(),
+        }
+    }
+
+    /// Create a new `LogListener` listening on the unix domain socket at `path`.
+    ///
+    /// The socket file is removed when the listener is stopped.
+    #[cfg(unix)]
+    pub fn unix<P: Into<PathBuf>>(path: P) -> Self {
+        LogListener {
+            endpoint: Ok(Endpoint::Unix(path.into())),
+            store: LogStore::global(),
+        }
+    }
+
+    /// Feed the received records into `store` instead of the global `LogStore`.
+    pub fn store(mut self, store: LogStore) -> Self {
+        self.store = store;
+        self
+    }
+
+    /// Bind the socket and start accepting connections on a background
+    /// thread. Cursive is asked to redraw through its callback sink whenever a
+    /// record arrives.
+    ///
+    /// The listener stops when the returned handle is dropped.
+    pub fn spawn(self, siv: &Cursive) -> io::Result<LogListenerHandle> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let receiver = Receiver {
+            store: self.store,
+            sink: siv.cb_sink().clone(),
+            stop: Arc::clone(&stop),
+        };
+
+        let (local_addr, socket_path, thread) = match self.endpoint? {
+            Endpoint::Tcp(addrs) => {
+                let listener = TcpListener::bind(&addrs[..])?;
+                listener.set_nonblocking(true)?;
+                let local_addr = listener.local_addr()?;
+                let thread = spawn_named("flexi-log-listener", move || {
+                    receiver.accept(|| {
+                        let (stream, addr) = listener.accept()?;
+                        stream.set_nonblocking(false)?;
+                        stream.set_read_timeout(Some(POLL_INTERVAL))?;
+                        Ok((stream, addr.to_string()))
+                    })
+                })?;
+                (Some(local_addr), None, thread)
+            }
+            #[cfg(unix)]
+            Endpoint::Unix(path) => {
+                let listener = UnixListener::bind(&path)?;
+                listener.set_nonblocking(true)?;
+                let mut connections = 0;
+                let thread = spawn_named("flexi-log-listener", move || {
+                    receiver.accept(|| {
+                        let (stream, _) = listener.accept()?;
+                        stream.set_nonblocking(false)?;
+                        stream.set_read_timeout(Some(POLL_INTERVAL))?;
+                        connections += 1;
+                        Ok((stream, format!("unix#{}", connections)))
+                    })
+                })?;
+                (None, Some(path), thread)
+            }
+        };
+
+        Ok(LogListenerHandle {
+            stop,
+            thread: Some(thread),
+            local_addr,
+            socket_path,
+        })
+    }
+}
+
+/// A handle to a running `LogListener`. Dropping it stops the listener.
+pub struct LogListenerHandle {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+    local_addr: Option<SocketAddr>,
+    socket_path: Option<PathBuf>,
+}
+
+impl LogListenerHandle {
+    /// The address the listener is bound to, if it listens on a TCP port.
+    ///
+    /// This is useful to find out the actual port when binding to port `0`.
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.local_addr
+    }
+
+    /// Stop accepting connections and wait for the background thread to finish.
+    pub fn stop(self) {
+        // the actual work is done in `Drop`
+    }
+}
+
+impl Drop for LogListenerHandle {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        if let Some(path) = &self.socket_path {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+fn spawn_named<F: FnOnce() + Send + 'static>(name: &str, f: F) -> io::Result<JoinHandle<()>> {
+    thread::Builder::new().name(name.to_string()).spawn(f)
+}
+
+#[derive(Clone)]
+struct Receiver {
+    store: LogStore,
+    sink: CbSink,
+    stop: Arc<AtomicBool>,
+}
+
+impl Receiver {
+    fn accept<S, F>(self, mut accept: F)
+    where
+        S: Read + Send + 'static,
+        F: FnMut() -> io::Result<(S, String)>,
+    {
+        let mut connections = Vec::new();
+        while !self.stop.load(Ordering::Relaxed) {
+            match accept() {
+                Ok((stream, peer)) => {
+                    let receiver = self.clone();
+                    if let Ok(thread) = spawn_named("flexi-log-connection", move || {
+                        receiver.receive(stream, peer)
+                    }) {
+                        connections.push(thread);
+                    }
+                }
+                // no pending connection (or a failed one), check again later
+                Err(_) => thread::sleep(POLL_INTERVAL),
+            }
+            connections.retain(|thread| !thread.is_finished());
+        }
+
+        for thread in connections {
+            let _ = thread.join();
+        }
+    }
+
+    fn receive<S: Read>(self, stream: S, peer: String) {
+        let mut reader = BufReader::new(stream);
+        let mut line = Vec::new();
+        while !self.stop.load(Ordering::Relaxed) {
+            // never buffer more than a line, whether the peer sends newlines
+            // or not
+            let limit = (MAX_LINE_LENGTH - line.len()) as u64;
+            match (&mut reader).take(limit).read_until(b'\n', &mut line) {
+                // the peer closed the connection
+                Ok(0) => break,
+                Ok(_) => {
+                    let text = String::from_utf8_lossy(&line);
+                    let text = text.trim_end_matches(&['\r', '\n'][..]);
+                    if !text.is_empty() {
+                        self.store.push(entry_from_json(text, &peer));
+                        if self.sink.send(Box::new(|_siv| {})).is_err() {
+                            break;
+                        }
+                    }
+                    line.clear();
+                }
+                Err(ref err)
+                    if err.kind() == io::ErrorKind::WouldBlock
+                        || err.kind() == io::ErrorKind::TimedOut
+                        || err.kind() == io::ErrorKind::Interrupted =>
+                {
+                    // the bytes read so far stay in `line`
+                }
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+/// Serialize an entry into a single line of JSON.
+fn entry_to_json(entry: &LogEntry) -> String {
+    json!({
+        "time": entry.time.format(&Rfc3339).ok(),
+        "level": entry.level.as_str(),
+        "thread": entry.thread,
+        "target": entry.target,
+        "file": entry.file,
+        "line": entry.line,
+        "message": entry.message,
+        "source": entry.source,
+        "fields": entry
+            .fields
+            .iter()
+            .map(|(key, value)| (key.clone(), Value::from(value.as_str())))
+            .collect::<Map<String, Value>>(),
+    })
+    .to_string()
+}
+
+/// Deserialize a line received from `peer`. Lines which are not a JSON log
+/// record are kept as plain messages, so nothing gets lost.
+fn entry_from_json(text: &str, peer: &str) -> LogEntry {
+    let value = serde_json::from_str::<Value>(text).ok();
+    let field = |name: &str| value.as_ref().and_then(|v| v.get(name));
+    let string = |name: &str| field(name).and_then(Value::as_str);
+
+    let level = string("level").and_then(|level| level.parse::<Level>().ok());
+    let message = match (level, string("message")) {
+        (Some(_), Some(message)) => message.to_string(),
+        _ => text.to_string(),
+    };
+
+    LogEntry {
+        seq: 0,
+        time: string("time")
+            .and_then(|time| OffsetDateTime::parse(time, &Rfc3339).ok())
+            .unwrap_or_else(|| *DeferredNow::new().now()),
+        level: level.unwrap_or(Level::Info),
+        thread: string("thread").unwrap_or("(unnamed)").to_string(),
+        target: string("target").unwrap_or_default().to_string(),
+        file: string("file").map(str::to_string),
+        line: field("line")
+            .and_then(Value::as_u64)
+            .and_then(|line| u32::try_from(line).ok()),
+        message,
+        source: Some(match string("source") {
+            Some(source) => format!("{}@{}", source, peer),
+            None => peer.to_string(),
+        }),
+        fields: field("fields")
+            .and_then(Value::as_object)
+            .map(|fields| {
+                fields
+                    .iter()
+                    .map(|(key, value)| match value {
+                        Value::String(value) => (key.clone(), value.clone()),
+                        value => (key.clone(), value.to_string()),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        json_start: None,
+    }
+}
+
+/// A `flexi_logger` `LogWriter` which ships log records to a `LogListener` in
+/// another process.
+///
+/// If the connection breaks, the writer tries to reconnect on the next record.
+///
+/// # Ship the logs of a child process to the TUI
+///
+/// ```rust,no_run
+/// use cursive_flexi_logger_view::RemoteLogWriter;
+/// use flexi_logger::Logger;
+///
+/// fn main() {
+///     let writer = RemoteLogWriter::tcp("127.0.0.1:4000")
+///         .expect("failed to connect to log listener")
+///         .source("worker");
+///
+///     Logger::try_with_env_or_str("trace")
+///         .expect("Could not create Logger from environment :(")
+///         .log_to_writer(Box::new(writer))
+///         .start()
+///         .expect("failed to initialize logger!");
+///
+///     log::info!("hello from the child process");
+/// }
+/// ```
+pub struct RemoteLogWriter {
+    endpoint: Endpoint,
+    source: Option<String>,
+    connection: Mutex<Option<Box<dyn Write + Send>>>,
+}
+
+impl RemoteLogWriter {
+    /// Connect to a `LogListener` listening on the TCP address `addr`.
+    pub fn tcp<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        Self::connect(Endpoint::Tcp(addr.to_socket_addrs()?.collect()))
+    }
+
+    /// Connect to a `LogListener` listening on the unix domain socket at `path`.
+    #[cfg(unix)]
+    pub fn unix<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::connect(Endpoint::Unix(path.as_ref().to_path_buf()))
+    }
+
+    /// Set the name the records are tagged with on the receiving side. Defaults
+    /// to the name of the executable.
+    pub fn source<S: Into<String>>(mut self, source: S) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    fn connect(endpoint: Endpoint) -> io::Result<Self> {
+        let connection = endpoint.connect()?;
+        let source = std::env::current_exe().ok().and_then(|exe| {
+            exe.file_stem()
+                .map(|name| format!("{}[{}]", name.to_string_lossy(), std::process::id()))
+        });
+        Ok(RemoteLogWriter {
+            endpoint,
+            source,
+            connection: Mutex::new(Some(connection)),
+        })
+    }
+}
+
+impl Endpoint {
+    fn connect(&self) -> io::Result<Box<dyn Write + Send>> {
+        Ok(match self {
+            Endpoint::Tcp(addrs) => Box::new(TcpStream::connect(&addrs[..])?),
+            #[cfg(unix)]
+            Endpoint::Unix(path) => Box::new(UnixStream::connect(path)?),
+        })
+    }
+}
+
+impl LogWriter for RemoteLogWriter {
+    fn write(&self, now: &mut DeferredNow, record: &Record) -> io::Result<()> {
+        let mut entry = LogEntry::from_record(now, record);
+        entry.source = self.source.clone();
+        let mut line = entry_to_json(&entry);
+        line.push('\n');
+
+        let mut connection = self.connection.lock().unwrap();
+        if connection.is_none() {
+            *connection = Some(self.endpoint.connect()?);
+        }
+        let result = connection
+            .as_mut()
+            .map_or(Ok(()), |stream| stream.write_all(line.as_bytes()));
+        if result.is_err() {
+            // reconnect with the next record
+            *connection = None;
+        }
+        result
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        match self.connection.lock().unwrap().as_mut() {
+            Some(stream) => stream.flush(),
+            None => Ok(()),
+        }
+    }
+
+    fn max_log_level(&self) -> log::LevelFilter {
+        log::LevelFilter::max()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Instant;
+
+    /// Returns the entries of `store` once it holds `count` of them, or after
+    /// a few seconds.
+    fn wait_for(store: &LogStore, count: usize) -> Vec<LogEntry> {
+        let start = Instant::now();
+        while store.snapshot().len() < count && start.elapsed() < Duration::from_secs(5) {
+            thread::sleep(Duration::from_millis(10));
+        }
+        store.snapshot()
+    }
+
+    #[test]
+    fn records_round_trip_over_tcp() {
+        let siv = Cursive::new();
+        let store = LogStore::new();
+        let listener = LogListener::tcp("127.0.0.1:0")
+            .store(store.clone())
+            .spawn(&siv)
+            .unwrap();
+
+        let writer = RemoteLogWriter::tcp(listener.local_addr().unwrap())
+            .unwrap()
+            .source("worker");
+        writer
+            .write(
+                &mut DeferredNow::new(),
+                &Record::builder()
+                    .args(format_args!("hello {}", "there"))
+                    .level(Level::Warn)
+                    .target("worker::net")
+                    .file(Some("src/net.rs"))
+                    .line(Some(12))
+                    .build(),
+            )
+            .unwrap();
+        writer.flush().unwrap();
+
+        let received = wait_for(&store, 1);
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].message, "hello there");
+        assert_eq!(received[0].level, Level::Warn);
+        assert_eq!(received[0].target, "worker::net");
+        assert_eq!(received[0].file.as_deref(), Some("src/net.rs"));
+        assert_eq!(received[0].line, Some(12));
+        let source = received[0].source.as_deref().unwrap();
+        assert!(source.starts_with("worker@127.0.0.1:"), "{}", source);
+    }
+
+    #[test]
+    fn long_lines_are_split() {
+        let siv = Cursive::new();
+        let store = LogStore::new();
+        let listener = LogListener::tcp("127.0.0.1:0")
+            .store(store.clone())
+            .spawn(&siv)
+            .unwrap();
+
+        let mut stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        stream
+            .write_all("x".repeat(MAX_LINE_LENGTH + 10).as_bytes())
+            .unwrap();
+        stream.write_all(b"\n").unwrap();
+
+        let lengths = wait_for(&store, 2)
+            .iter()
+            .map(|entry| entry.message.len())
+            .collect::<Vec<_>>();
+        assert_eq!(lengths, [MAX_LINE_LENGTH, 10]);
+    }
+
+    #[test]
+    fn keeps_lines_which_are_no_records() {
+        let entry = entry_from_json("plain text", "peer");
+        assert_eq!(entry.message, "plain text");
+        assert_eq!(entry.level, Level::Info);
+        assert_eq!(entry.source.as_deref(), Some("peer"));
+
+        // JSON which is no log record is kept as it is, too
+        let entry = entry_from_json(r#"{"status": "ok"}"#, "peer");
+        assert_eq!(entry.message, r#"{"status": "ok"}"#);
+
+        let entry = entry_from_json(
+            r#"{"level": "ERROR", "message": "broken", "source": "worker", "fields": {"id": 7}}"#,
+            "peer",
+        );
+        assert_eq!(entry.message, "broken");
+        assert_eq!(entry.level, Level::Error);
+        assert_eq!(entry.source.as_deref(), Some("worker@peer"));
+        assert_eq!(entry.field("id"), Some("7"));
+    }
+}
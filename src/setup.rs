@@ -0,0 +1,150 @@
+use cursive_core::Cursive;
+use flexi_logger::{FileSpec, FlexiLoggerError, FormatFunction, Logger, LoggerHandle};
+
+use std::path::PathBuf;
+
+use crate::{cursive_flexi_logger, LogStore, RateLimits};
+
+/// The configuration of the logger started by `setup`.
+///
+/// By default, the spec is read from `RUST_LOG`, falling back to `info`, and
+/// records are written to `logs/<program>.log` with
+/// `flexi_logger::colored_with_thread` as well as to the global `LogStore`.
+#[derive(Clone)]
+pub struct SetupOptions {
+    spec: String,
+    env: bool,
+    directory: Option<PathBuf>,
+    format: FormatFunction,
+    store: LogStore,
+    non_blocking: Option<usize>,
+    rate_limits: Option<RateLimits>,
+}
+
+impl SetupOptions {
+    /// Create the default configuration.
+    pub fn new() -> Self {
+        SetupOptions {
+            spec: "info".to_string(),
+            env: true,
+            directory: Some(PathBuf::from("logs")),
+            format: flexi_logger::colored_with_thread,
+            store: LogStore::global(),
+            non_blocking: None,
+            rate_limits: None,
+        }
+    }
+
+    /// Use `spec`, e.g. `info, my_crate::net=trace`, if `RUST_LOG` is not set.
+    pub fn spec<S: Into<String>>(mut self, spec: S) -> Self {
+        self.spec = spec.into();
+        self
+    }
+
+    /// Ignore `RUST_LOG` and always use the spec passed to `spec`.
+    pub fn ignore_env(mut self) -> Self {
+        self.env = false;
+        self
+    }
+
+    /// Write the log file to `directory` instead of `logs`.
+    pub fn directory<P: Into<PathBuf>>(mut self, directory: P) -> Self {
+        self.directory = Some(directory.into());
+        self
+    }
+
+    /// Do not write a log file, only to the store.
+    pub fn no_file(mut self) -> Self {
+        self.directory = None;
+        self
+    }
+
+    /// Format the records of the log file with `format`.
+    pub fn format(mut self, format: FormatFunction) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Push the records to `store` instead of the global store.
+    pub fn store(mut self, store: LogStore) -> Self {
+        self.store = store;
+        self
+    }
+
+    /// Hand the records to a background thread, see
+    /// `CursiveLogWriter::non_blocking`.
+    pub fn non_blocking(mut self, capacity: usize) -> Self {
+        self.non_blocking = Some(capacity);
+        self
+    }
+
+    /// Limit the rate of records per target, see `RateLimits`.
+    pub fn rate_limits(mut self, limits: RateLimits) -> Self {
+        self.rate_limits = Some(limits);
+        self
+    }
+}
+
+impl Default for SetupOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Start a `flexi_logger` which writes to the views of `siv`, configured by
+/// `options`. Returns the handle of the logger, e.g. for a `LogSpecEditor`,
+/// and the store the records are pushed to.
+///
+/// The logger needs the callback sink of `siv` to redraw on new records, so
+/// call this right after creating `siv`. Keep the handle alive while logging,
+/// as dropping it flushes and closes the log file.
+///
+/// # Log to `logs` and the debug console
+///
+/// ```rust
+/// use cursive::{Cursive, CursiveExt};
+/// use cursive_flexi_logger_view::{setup, DebugConsole, LogSpecEditor, SetupOptions};
+///
+/// fn main() {
+///     let mut siv = Cursive::default();
+///
+///     let (handle, store) = setup(&siv, SetupOptions::new().spec("debug"))
+///         .expect("failed to initialize logger!");
+///
+///     let console = DebugConsole::new()
+///         .store(store)
+///         .spec_editor(LogSpecEditor::new(handle));
+///     siv.add_global_callback('~', move |siv| console.toggle(siv));
+///
+///     // siv.run();
+/// }
+/// ```
+pub fn setup(
+    siv: &Cursive,
+    options: SetupOptions,
+) -> Result<(LoggerHandle, LogStore), FlexiLoggerError> {
+    let mut writer = cursive_flexi_logger(siv).store(options.store.clone());
+    if let Some(limits) = options.rate_limits {
+        writer = writer.rate_limits(limits);
+    }
+    if let Some(capacity) = options.non_blocking {
+        writer = writer.non_blocking(capacity)?;
+    }
+
+    let logger = if options.env {
+        Logger::try_with_env_or_str(&options.spec)?
+    } else {
+        Logger::try_with_str(&options.spec)?
+    };
+    let logger = match options.directory {
+        Some(directory) => logger.log_to_file_and_writer(
+            FileSpec::default()
+                .directory(directory)
+                .suppress_timestamp(),
+            writer,
+        ),
+        None => logger.log_to_writer(writer),
+    };
+    let handle = logger.format(options.format).start()?;
+    Ok((handle, options.store))
+}
@@ -0,0 +1,156 @@
+use cursive_core::utils::markup::StyledString;
+use cursive_core::view::{Nameable, Resizable};
+use cursive_core::views::{Dialog, EditView, LinearLayout, TextView};
+use cursive_core::Cursive;
+use flexi_logger::{FlexiLoggerError, Level, LogSpecification, LoggerHandle};
+
+use std::sync::{Arc, Mutex};
+
+use crate::level_color;
+
+static FLEXI_LOGGER_SPEC_INPUT_NAME: &str = "_flexi_spec_input";
+static FLEXI_LOGGER_SPEC_ERROR_NAME: &str = "_flexi_spec_error";
+
+struct SpecState {
+    handle: LoggerHandle,
+    /// The specs applied by the editor which can still be restored, the
+    /// current one last.
+    applied: Vec<String>,
+}
+
+/// Changes the `LogSpecification` of a running logger, e.g. to raise the
+/// verbosity of a module without restarting with a new `RUST_LOG`.
+///
+/// Typed specs are pushed as temporary specs of the `LoggerHandle`, so the
+/// previous spec can be restored with `restore`. Clones share the handle and
+/// the specs to restore. In a `DebugConsole`, the editor is shown with `v`
+/// and the previous spec is restored with `V` (see `LogViewKeymap`).
+///
+/// # Edit the spec in the debug console
+///
+/// ```rust
+/// use cursive::{Cursive, CursiveExt};
+/// use cursive_flexi_logger_view::{DebugConsole, LogSpecEditor};
+/// use flexi_logger::Logger;
+///
+/// fn main() {
+///     let mut siv = Cursive::default();
+///
+///     let handle = Logger::try_with_env_or_str("info")
+///         .expect("Could not create Logger from environment :(")
+///         .log_to_writer(cursive_flexi_logger_view::cursive_flexi_logger(&siv))
+///         .start()
+///         .expect("failed to initialize logger!");
+///
+///     let console = DebugConsole::new().spec_editor(LogSpecEditor::new(handle));
+///     siv.add_global_callback('~', move |siv| console.toggle(siv));
+///
+///     // siv.run();
+/// }
+/// ```
+#[derive(Clone)]
+pub struct LogSpecEditor {
+    state: Arc<Mutex<SpecState>>,
+}
+
+impl LogSpecEditor {
+    /// Create an editor for the logger of `handle`.
+    pub fn new(handle: LoggerHandle) -> Self {
+        LogSpecEditor {
+            state: Arc::new(Mutex::new(SpecState {
+                handle,
+                applied: Vec::new(),
+            })),
+        }
+    }
+
+    /// Parse `spec` and replace the current spec with it, keeping the current
+    /// one to be restored.
+    pub fn apply(&self, spec: &str) -> Result<(), FlexiLoggerError> {
+        // parse first, as `parse_and_push_temp_spec` stacks the current spec
+        // even if parsing fails
+        let parsed = LogSpecification::parse(spec)?;
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.handle.push_temp_spec(parsed);
+        state.applied.push(spec.to_string());
+        Ok(())
+    }
+
+    /// Parse `spec` and replace the current spec with it for good, dropping
+    /// all specs to restore.
+    pub fn set(&self, spec: &str) -> Result<(), FlexiLoggerError> {
+        let parsed = LogSpecification::parse(spec)?;
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        while state.applied.pop().is_some() {
+            state.handle.pop_temp_spec();
+        }
+        state.handle.set_new_spec(parsed);
+        Ok(())
+    }
+
+    /// Restore the spec which was replaced by the last `apply`. Returns
+    /// `false` if there is none.
+    pub fn restore(&self) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.applied.pop().is_none() {
+            return false;
+        }
+        state.handle.pop_temp_spec();
+        true
+    }
+
+    /// Returns the spec applied last, if it was not restored yet.
+    pub fn current(&self) -> Option<String> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.applied.last().cloned()
+    }
+
+    /// Show a dialog to type a spec like `info, my_crate::net=trace`. Parse
+    /// errors are shown below the input.
+    pub fn show(&self, siv: &mut Cursive) {
+        let editor = self.clone();
+        let apply = move |siv: &mut Cursive, spec: &str| match editor.apply(spec) {
+            Ok(()) => {
+                siv.pop_layer();
+            }
+            Err(err) => {
+                siv.call_on_name(FLEXI_LOGGER_SPEC_ERROR_NAME, |text: &mut TextView| {
+                    text.set_content(StyledString::styled(
+                        err.to_string(),
+                        level_color(Level::Error),
+                    ))
+                });
+            }
+        };
+        let submit = apply.clone();
+        let restore = self.clone();
+
+        siv.add_layer(
+            Dialog::around(
+                LinearLayout::vertical()
+                    .child(
+                        EditView::new()
+                            .content(self.current().unwrap_or_default())
+                            .on_submit(submit)
+                            .with_name(FLEXI_LOGGER_SPEC_INPUT_NAME)
+                            .min_width(40),
+                    )
+                    .child(TextView::empty().with_name(FLEXI_LOGGER_SPEC_ERROR_NAME)),
+            )
+            .title("Log specification")
+            .button("Apply", move |siv| {
+                let spec = siv
+                    .call_on_name(FLEXI_LOGGER_SPEC_INPUT_NAME, |edit: &mut EditView| {
+                        edit.get_content()
+                    })
+                    .unwrap_or_default();
+                apply(siv, &spec);
+            })
+            .button("Restore", move |siv| {
+                restore.restore();
+                siv.pop_layer();
+            })
+            .dismiss_button("Cancel"),
+        );
+    }
+}
@@ -0,0 +1,655 @@
+use arraydeque::{ArrayDeque, Wrapping};
+use flexi_logger::{DeferredNow, Level, Record};
+use time::OffsetDateTime;
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::convert::TryFrom;
+use std::ops::{Deref, DerefMut, RangeBounds};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LockResult, Mutex, MutexGuard, PoisonError, TryLockError, Weak};
+use std::thread;
+use std::time::Duration;
+
+use crate::{json, LogFilter};
+
+type Entries = ArrayDeque<[LogEntry; 2048], Wrapping>;
+
+/// How many seconds of record rates are kept.
+const RATE_HISTORY: usize = 600;
+
+type Subscribers = Mutex<Vec<Subscriber>>;
+
+type ReleaseCallback = Box<dyn FnOnce(&LogBuffer) + Send>;
+
+lazy_static::lazy_static! {
+    static ref LOGS: LogStore = LogStore::new();
+}
+
+static NEXT_SUBSCRIBER: AtomicU64 = AtomicU64::new(0);
+
+/// A single log record as it is kept in a `LogStore`.
+#[derive(Clone, Debug)]
+pub struct LogEntry {
+    /// The sequence number of the entry, which is assigned by the `LogStore`
+    /// when the entry is pushed. It increases with every entry and identifies
+    /// an entry even after older entries have been dropped.
+    pub seq: u64,
+    /// The point in time the record was created.
+    pub time: OffsetDateTime,
+    /// The log level of the record.
+    pub level: Level,
+    /// The name of the thread which emitted the record.
+    pub thread: String,
+    /// The target of the record, usually the module path.
+    pub target: String,
+    /// The source file the record originates from, if known.
+    pub file: Option<String>,
+    /// The line in the source file the record originates from, if known.
+    pub line: Option<u32>,
+    /// The formatted log message.
+    pub message: String,
+    /// The origin of the record if it does not stem from this process, e.g. the
+    /// name of a followed log file.
+    pub source: Option<String>,
+    /// The structured key-value pairs attached to the record, in the order
+    /// they were logged. Records of this process only carry them if the `kv`
+    /// feature of this crate is enabled.
+    pub fields: Vec<(String, String)>,
+    /// The byte offset of the JSON object or array which makes up the end of
+    /// the message, if any. Like the sequence number, it is set by the
+    /// `LogStore` when the entry is pushed, so views don't have to search
+    /// messages for JSON whenever they are drawn.
+    pub json_start: Option<usize>,
+}
+
+impl LogEntry {
+    /// Create an entry for a record logged in this process.
+    pub(crate) fn from_record(now: &mut DeferredNow, record: &Record) -> Self {
+        LogEntry {
+            seq: 0,
+            time: *now.now(),
+            level: record.level(),
+            thread: thread_name(),
+            target: record.target().to_string(),
+            file: record.file().map(str::to_string),
+            line: record.line(),
+            message: record.args().to_string(),
+            source: None,
+            fields: fields(record),
+            json_start: None,
+        }
+    }
+
+    /// Returns the value of the key-value pair named `key`, if any.
+    pub fn field(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Collect the key-value pairs of `record`.
+#[cfg(feature = "kv")]
+fn fields(record: &Record) -> Vec<(String, String)> {
+    struct Collect(Vec<(String, String)>);
+
+    impl<'kvs> log::kv::VisitSource<'kvs> for Collect {
+        fn visit_pair(
+            &mut self,
+            key: log::kv::Key<'kvs>,
+            value: log::kv::Value<'kvs>,
+        ) -> Result<(), log::kv::Error> {
+            self.0.push((key.to_string(), value.to_string()));
+            Ok(())
+        }
+    }
+
+    let mut collect = Collect(Vec::new());
+    let _ = record.key_values().visit(&mut collect);
+    collect.0
+}
+
+#[cfg(not(feature = "kv"))]
+fn fields(_record: &Record) -> Vec<(String, String)> {
+    Vec::new()
+}
+
+/// Returns the name of the current thread. Unnamed threads are identified by
+/// their `ThreadId`.
+pub(crate) fn thread_name() -> String {
+    let current = thread::current();
+    match current.name() {
+        Some(name) => name.to_string(),
+        None => format!("{:?}", current.id()),
+    }
+}
+
+/// A ring buffer of log entries which is shared between log writers, log
+/// sources and `FlexiLoggerView`s.
+///
+/// Cloning a `LogStore` is cheap and yields a handle to the same buffer. Unless
+/// configured otherwise, all views and writers of this crate use the store
+/// returned by `LogStore::global`.
+///
+/// The store stays usable if a thread panics while holding it. Entries pushed
+/// by a thread which currently holds the store, e.g. when a record is logged
+/// while a view is drawn, are appended as soon as the store is released.
+#[derive(Clone)]
+pub struct LogStore {
+    buffer: Arc<Mutex<LogBuffer>>,
+    deferred: Arc<Mutex<Vec<LogEntry>>>,
+    released: Arc<Mutex<Vec<ReleaseCallback>>>,
+    dropped: Arc<AtomicU64>,
+    subscribers: Arc<Subscribers>,
+}
+
+#[derive(Clone)]
+struct Subscriber {
+    id: u64,
+    filter: LogFilter,
+    callback: Arc<dyn Fn(&LogEntry) + Send + Sync>,
+}
+
+thread_local! {
+    /// The buffers of the stores which are locked by the current thread.
+    static HELD: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+impl LogStore {
+    /// Create a new, empty `LogStore` which is independent of the global one.
+    pub fn new() -> Self {
+        LogStore {
+            buffer: Arc::new(Mutex::new(LogBuffer::new())),
+            deferred: Arc::new(Mutex::new(Vec::new())),
+            released: Arc::new(Mutex::new(Vec::new())),
+            dropped: Arc::new(AtomicU64::new(0)),
+            subscribers: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Returns a handle to the global `LogStore` used by `cursive_flexi_logger`
+    /// and `FlexiLoggerView::new`.
+    pub fn global() -> Self {
+        LOGS.clone()
+    }
+
+    /// Append an entry to the store. When the store is full, the oldest entry
+    /// is dropped.
+    pub fn push(&self, entry: LogEntry) {
+        if self.is_held() {
+            recover(self.deferred.lock()).push(entry);
+        } else {
+            self.lock().push(entry);
+        }
+    }
+
+    /// Append multiple entries to the store at once.
+    pub fn extend<I: IntoIterator<Item = LogEntry>>(&self, entries: I) {
+        if self.is_held() {
+            recover(self.deferred.lock()).extend(entries);
+            return;
+        }
+        let mut buffer = self.lock();
+        for entry in entries {
+            buffer.push(entry);
+        }
+    }
+
+    /// Remove all entries and bookmarks from the store. Sequence numbers keep
+    /// counting, so entries pushed afterwards are still ordered after the
+    /// removed ones.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Returns a copy of all entries in the store, oldest first.
+    pub fn snapshot(&self) -> Vec<LogEntry> {
+        self.lock().iter().cloned().collect()
+    }
+
+    /// Iterate over copies of the entries at the positions in `indices`,
+    /// where `0` is the oldest entry in the store.
+    ///
+    /// ```rust
+    /// use cursive_flexi_logger_view::LogStore;
+    ///
+    /// // the last ten entries of the global store
+    /// let store = LogStore::global();
+    /// let len = store.snapshot().len();
+    /// for entry in store.range(len.saturating_sub(10)..) {
+    ///     println!("{}: {}", entry.seq, entry.message);
+    /// }
+    /// ```
+    pub fn range<R: RangeBounds<usize>>(&self, indices: R) -> impl Iterator<Item = LogEntry> {
+        let logs = self.lock();
+        logs.iter()
+            .enumerate()
+            .skip_while(|(index, _)| !indices.contains(index))
+            .take_while(|(index, _)| indices.contains(index))
+            .map(|(_, entry)| entry.clone())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Iterate over copies of the entries with a sequence number in `seqs`,
+    /// e.g. the entries pushed since the last time the store was read.
+    pub fn seq_range<R: RangeBounds<u64>>(&self, seqs: R) -> impl Iterator<Item = LogEntry> {
+        let logs = self.lock();
+        logs.iter()
+            .skip_while(|entry| !seqs.contains(&entry.seq))
+            .take_while(|entry| seqs.contains(&entry.seq))
+            .cloned()
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Bookmark the entry with the sequence number `seq`, or remove its
+    /// bookmark if it is already bookmarked. Returns whether the entry is
+    /// bookmarked now.
+    ///
+    /// Bookmarked entries are kept, even when the store is full and they would
+    /// be dropped otherwise.
+    pub fn toggle_bookmark(&self, seq: u64) -> bool {
+        self.lock().toggle_bookmark(seq)
+    }
+
+    /// Returns whether the entry with the sequence number `seq` is bookmarked.
+    pub fn is_bookmarked(&self, seq: u64) -> bool {
+        self.lock().is_bookmarked(seq)
+    }
+
+    /// Returns the names of all threads which emitted an entry into the store,
+    /// in alphabetical order.
+    pub fn seen_threads(&self) -> Vec<String> {
+        self.lock().threads.iter().cloned().collect()
+    }
+
+    /// Returns the sequence numbers of all bookmarked entries in ascending order.
+    pub fn bookmarks(&self) -> Vec<u64> {
+        self.lock().bookmarks.iter().copied().collect()
+    }
+
+    /// Returns how many entries were dropped from the store, because it was
+    /// full.
+    pub fn evicted_records(&self) -> u64 {
+        self.lock().evicted
+    }
+
+    /// Returns how many entries were pushed in each of the last `seconds`
+    /// seconds, oldest first. The last element counts the current second.
+    /// The rates of the last 10 minutes are kept, even if the entries
+    /// themselves were dropped or cleared.
+    pub fn record_rates(&self, seconds: usize) -> Vec<LevelCounts> {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let mut rates = vec![LevelCounts::default(); seconds];
+        for (second, counts) in &self.lock().rates {
+            let ago = usize::try_from(now - second).unwrap_or(usize::MAX);
+            if ago < seconds {
+                rates[seconds - 1 - ago] = *counts;
+            }
+        }
+        rates
+    }
+
+    /// Returns how many records never made it into the store, because the
+    /// queue of a non-blocking writer was full.
+    pub fn dropped_records(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Call `callback` with each new entry passing `filter`, until the returned
+    /// subscription is dropped.
+    ///
+    /// The callback runs on the thread which pushed the entry, after the store
+    /// has been released, so it may read the store or log records. Records
+    /// logged by the callback are passed to it as well, unless they are
+    /// filtered out.
+    ///
+    /// # Count the errors
+    ///
+    /// ```rust
+    /// use cursive_flexi_logger_view::{LogFilter, LogStore};
+    /// use flexi_logger::Level;
+    ///
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// let errors = Arc::new(AtomicUsize::new(0));
+    /// let counter = Arc::clone(&errors);
+    /// let _subscription = LogStore::global().subscribe(
+    ///     LogFilter::new().with_level(Level::Error),
+    ///     move |_entry| {
+    ///         counter.fetch_add(1, Ordering::Relaxed);
+    ///     },
+    /// );
+    /// ```
+    pub fn subscribe<F>(&self, filter: LogFilter, callback: F) -> LogSubscription
+    where
+        F: Fn(&LogEntry) + Send + Sync + 'static,
+    {
+        let id = NEXT_SUBSCRIBER.fetch_add(1, Ordering::Relaxed);
+        recover(self.subscribers.lock()).push(Subscriber {
+            id,
+            filter,
+            callback: Arc::new(callback),
+        });
+        LogSubscription {
+            subscribers: Arc::downgrade(&self.subscribers),
+            id,
+        }
+    }
+
+    /// Pass `entries` to the subscribers whose filter they pass.
+    fn notify(&self, entries: &[LogEntry]) {
+        // callbacks may subscribe or unsubscribe
+        let subscribers = recover(self.subscribers.lock()).clone();
+        for entry in entries {
+            for subscriber in &subscribers {
+                if subscriber.filter.matches(entry) {
+                    (subscriber.callback)(entry);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn count_dropped(&self) {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Lock the buffer. A buffer poisoned by a panic is used as is.
+    ///
+    /// This must not be called by a thread which already holds the buffer.
+    pub(crate) fn lock(&self) -> BufferGuard<'_> {
+        BufferGuard::new(self, recover(self.buffer.lock()))
+    }
+
+    /// Lock the buffer without blocking for long, e.g. from a panic hook. Gives
+    /// up if the buffer stays locked or is held by the current thread.
+    pub(crate) fn try_lock(&self) -> Option<BufferGuard<'_>> {
+        if self.is_held() {
+            return None;
+        }
+        for _ in 0..10 {
+            match self.buffer.try_lock() {
+                Ok(buffer) => return Some(BufferGuard::new(self, buffer)),
+                Err(TryLockError::Poisoned(poisoned)) => {
+                    return Some(BufferGuard::new(self, poisoned.into_inner()))
+                }
+                Err(TryLockError::WouldBlock) => thread::sleep(Duration::from_millis(10)),
+            }
+        }
+        None
+    }
+
+    /// Call `callback` with the buffer when the current thread releases it,
+    /// after the deferred entries were appended. This must only be called by
+    /// a thread which holds the buffer.
+    pub(crate) fn on_release<F>(&self, callback: F)
+    where
+        F: FnOnce(&LogBuffer) + Send + 'static,
+    {
+        recover(self.released.lock()).push(Box::new(callback));
+    }
+
+    /// Returns whether the current thread holds the buffer.
+    pub(crate) fn is_held(&self) -> bool {
+        let id = self.id();
+        HELD.with(|held| held.borrow().contains(&id))
+    }
+
+    fn id(&self) -> usize {
+        Arc::as_ptr(&self.buffer) as usize
+    }
+}
+
+/// Ignore the poisoning of a lock, the guarded data stays consistent enough to
+/// keep logging.
+fn recover<T>(result: LockResult<T>) -> T {
+    result.unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Exclusive access to the buffer of a `LogStore`. Entries deferred while the
+/// buffer was held are appended when the guard is dropped, and the callbacks
+/// registered with `LogStore::on_release` are called. Afterwards the buffer
+/// is released and the subscribers are notified of the new entries.
+pub(crate) struct BufferGuard<'a> {
+    store: &'a LogStore,
+    buffer: Option<MutexGuard<'a, LogBuffer>>,
+    /// Copies of the pushed entries, if the store has subscribers.
+    pushed: Option<Vec<LogEntry>>,
+}
+
+impl<'a> BufferGuard<'a> {
+    fn new(store: &'a LogStore, buffer: MutexGuard<'a, LogBuffer>) -> Self {
+        HELD.with(|held| held.borrow_mut().push(store.id()));
+        let subscribed = !recover(store.subscribers.lock()).is_empty();
+        BufferGuard {
+            store,
+            buffer: Some(buffer),
+            pushed: Some(Vec::new()).filter(|_| subscribed),
+        }
+    }
+
+    /// Append an entry to the buffer.
+    pub(crate) fn push(&mut self, entry: LogEntry) {
+        let seq = self.buffer().next_seq;
+        if let Some(pushed) = &mut self.pushed {
+            let mut copy = entry.clone();
+            copy.seq = seq;
+            pushed.push(copy);
+        }
+        self.buffer_mut().push(entry);
+    }
+
+    fn buffer(&self) -> &LogBuffer {
+        self.buffer
+            .as_ref()
+            .expect("buffer is held until the guard is dropped")
+    }
+
+    fn buffer_mut(&mut self) -> &mut LogBuffer {
+        self.buffer
+            .as_mut()
+            .expect("buffer is held until the guard is dropped")
+    }
+}
+
+impl Deref for BufferGuard<'_> {
+    type Target = LogBuffer;
+
+    fn deref(&self) -> &LogBuffer {
+        self.buffer()
+    }
+}
+
+impl DerefMut for BufferGuard<'_> {
+    fn deref_mut(&mut self) -> &mut LogBuffer {
+        self.buffer_mut()
+    }
+}
+
+impl Drop for BufferGuard<'_> {
+    fn drop(&mut self) {
+        let deferred = std::mem::take(&mut *recover(self.store.deferred.lock()));
+        for entry in deferred {
+            self.push(entry);
+        }
+        let released = std::mem::take(&mut *recover(self.store.released.lock()));
+        for callback in released {
+            callback(self.buffer());
+        }
+        self.buffer = None;
+        let id = self.store.id();
+        HELD.with(|held| {
+            let mut held = held.borrow_mut();
+            if let Some(index) = held.iter().position(|other| *other == id) {
+                held.swap_remove(index);
+            }
+        });
+        match self.pushed.take() {
+            Some(pushed) if !pushed.is_empty() => self.store.notify(&pushed),
+            _ => {}
+        }
+    }
+}
+
+/// Keeps a callback registered with `LogStore::subscribe` subscribed. The
+/// callback is removed when the subscription is dropped.
+pub struct LogSubscription {
+    subscribers: Weak<Subscribers>,
+    id: u64,
+}
+
+impl Drop for LogSubscription {
+    fn drop(&mut self) {
+        if let Some(subscribers) = self.subscribers.upgrade() {
+            recover(subscribers.lock()).retain(|subscriber| subscriber.id != self.id);
+        }
+    }
+}
+
+impl Default for LogStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The number of log entries per level, e.g. in one second.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LevelCounts([u64; 5]);
+
+impl LevelCounts {
+    /// Returns the number of entries at `level`.
+    pub fn get(&self, level: Level) -> u64 {
+        self.0[level as usize - 1]
+    }
+
+    /// Returns the number of entries at all levels.
+    pub fn total(&self) -> u64 {
+        self.0.iter().sum()
+    }
+
+    fn count(&mut self, level: Level) {
+        self.0[level as usize - 1] += 1;
+    }
+}
+
+/// The content of a `LogStore`.
+///
+/// Bookmarked entries which got dropped from the ring buffer are kept in
+/// `pinned`. As they are older than any entry in the ring buffer, they come
+/// first when iterating.
+pub(crate) struct LogBuffer {
+    pinned: BTreeMap<u64, LogEntry>,
+    entries: Entries,
+    bookmarks: BTreeSet<u64>,
+    threads: BTreeSet<String>,
+    next_seq: u64,
+    evicted: u64,
+    /// The entries pushed per second, by unix timestamp.
+    rates: VecDeque<(i64, LevelCounts)>,
+}
+
+impl LogBuffer {
+    fn new() -> Self {
+        LogBuffer {
+            pinned: BTreeMap::new(),
+            entries: Entries::new(),
+            bookmarks: BTreeSet::new(),
+            threads: BTreeSet::new(),
+            next_seq: 0,
+            evicted: 0,
+            rates: VecDeque::new(),
+        }
+    }
+
+    fn push(&mut self, mut entry: LogEntry) {
+        entry.seq = self.next_seq;
+        self.next_seq += 1;
+        entry.json_start = json::split_json(&entry.message).map(|(text, _)| text.len());
+        if !self.threads.contains(&entry.thread) {
+            self.threads.insert(entry.thread.clone());
+        }
+        self.count_rate(entry.level);
+        if let Some(dropped) = self.entries.push_back(entry) {
+            if self.bookmarks.contains(&dropped.seq) {
+                self.pinned.insert(dropped.seq, dropped);
+            } else {
+                self.evicted += 1;
+            }
+        }
+    }
+
+    fn count_rate(&mut self, level: Level) {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        match self.rates.back_mut() {
+            Some((second, counts)) if *second == now => counts.count(level),
+            _ => {
+                let mut counts = LevelCounts::default();
+                counts.count(level);
+                self.rates.push_back((now, counts));
+            }
+        }
+        while self.rates.len() > RATE_HISTORY {
+            self.rates.pop_front();
+        }
+    }
+
+    /// Remove all entries and bookmarks. Sequence numbers keep counting.
+    pub(crate) fn clear(&mut self) {
+        self.pinned.clear();
+        self.entries.clear();
+        self.bookmarks.clear();
+    }
+
+    fn toggle_bookmark(&mut self, seq: u64) -> bool {
+        if self.bookmarks.remove(&seq) {
+            self.pinned.remove(&seq);
+            false
+        } else if self.position(seq).is_some() {
+            self.bookmarks.insert(seq)
+        } else {
+            false
+        }
+    }
+
+    pub(crate) fn is_bookmarked(&self, seq: u64) -> bool {
+        self.bookmarks.contains(&seq)
+    }
+
+    /// Returns the sequence number of the nearest bookmark after (or before)
+    /// the entry with sequence number `seq`.
+    pub(crate) fn next_bookmark(&self, seq: Option<u64>, forward: bool) -> Option<u64> {
+        match (seq, forward) {
+            (Some(seq), true) => self.bookmarks.range(seq + 1..).next(),
+            (None, true) => self.bookmarks.iter().next(),
+            (Some(seq), false) => self.bookmarks.range(..seq).next_back(),
+            (None, false) => self.bookmarks.iter().next_back(),
+        }
+        .copied()
+    }
+
+    pub(crate) fn iter(&self) -> impl DoubleEndedIterator<Item = &LogEntry> + Clone {
+        self.pinned.values().chain(self.entries.iter())
+    }
+
+    /// Returns the position of the entry with the sequence number `seq`.
+    pub(crate) fn position(&self, seq: u64) -> Option<usize> {
+        if let Some(index) = self.pinned.keys().position(|pinned| *pinned == seq) {
+            return Some(index);
+        }
+        let first = self.entries.front()?.seq;
+        let index = usize::try_from(seq.checked_sub(first)?).ok()?;
+        self.entries.get(index).filter(|entry| entry.seq == seq)?;
+        Some(self.pinned.len() + index)
+    }
+
+    pub(crate) fn get(&self, index: usize) -> Option<&LogEntry> {
+        match index.checked_sub(self.pinned.len()) {
+            Some(index) => self.entries.get(index),
+            None => self.pinned.values().nth(index),
+        }
+    }
+}
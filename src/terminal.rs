@@ -0,0 +1,58 @@
+/// Leave the alternate screen and show the cursor.
+pub(crate) const LEAVE_SCREEN: &str = "\x1b[?1049l\x1b[?25h";
+
+/// Enter the alternate screen again and hide the cursor.
+pub(crate) const ENTER_SCREEN: &str = "\x1b[?1049h\x1b[?25l";
+
+/// Stop all mouse reporting modes: normal, button-event and any-event
+/// tracking, as well as the RXVT and SGR coordinate encodings. This covers
+/// the modes enabled by the crossterm, termion and ncurses backends.
+pub(crate) const DISABLE_MOUSE: &str = "\x1b[?1006l\x1b[?1015l\x1b[?1003l\x1b[?1002l\x1b[?1000l";
+
+/// Start the mouse reporting modes enabled by the crossterm backend (its
+/// `EnableMouseCapture`) again.
+pub(crate) const ENABLE_MOUSE: &str = "\x1b[?1000h\x1b[?1002h\x1b[?1003h\x1b[?1015h\x1b[?1006h";
+
+/// Reset all colors and effects.
+pub(crate) const RESET_STYLE: &str = "\x1b[0m";
+
+/// The settings of the terminal on stdin, e.g. to leave the raw mode of
+/// cursive's backend.
+#[cfg(unix)]
+pub(crate) struct TerminalMode(libc::termios);
+
+#[cfg(unix)]
+impl TerminalMode {
+    /// Returns the current settings, if stdin is a terminal.
+    pub(crate) fn current() -> Option<Self> {
+        let mut termios = std::mem::MaybeUninit::uninit();
+        // SAFETY: `tcgetattr` initializes `termios` if it succeeds
+        unsafe {
+            if libc::tcgetattr(libc::STDIN_FILENO, termios.as_mut_ptr()) != 0 {
+                return None;
+            }
+            Some(TerminalMode(termios.assume_init()))
+        }
+    }
+
+    /// Apply the settings to the terminal again.
+    pub(crate) fn restore(&self) {
+        // SAFETY: `self.0` is a valid `termios` returned by `tcgetattr`
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.0);
+        }
+    }
+}
+
+/// The settings of the terminal, which are only known on unix platforms.
+#[cfg(not(unix))]
+pub(crate) struct TerminalMode;
+
+#[cfg(not(unix))]
+impl TerminalMode {
+    pub(crate) fn current() -> Option<Self> {
+        None
+    }
+
+    pub(crate) fn restore(&self) {}
+}
@@ -0,0 +1,245 @@
+use cursive::backends::puppet::Backend;
+use cursive::{Cursive, Vec2};
+use cursive_flexi_logger_view::{
+    FlexiLoggerView, LogDump, LogEntry, LogFilter, LogStore, PanicHook,
+};
+use flexi_logger::writers::LogWriter;
+use flexi_logger::{DeferredNow, Level};
+use time::OffsetDateTime;
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{mpsc, Arc, Mutex};
+
+fn entry(message: &str) -> LogEntry {
+    LogEntry {
+        seq: 0,
+        time: OffsetDateTime::UNIX_EPOCH,
+        level: Level::Info,
+        thread: "test".to_string(),
+        target: "store".to_string(),
+        file: None,
+        line: None,
+        message: message.to_string(),
+        source: None,
+        fields: Vec::new(),
+        json_start: None,
+    }
+}
+
+fn messages(store: &LogStore) -> Vec<String> {
+    let mut out = Vec::new();
+    LogDump::new()
+        .store(store.clone())
+        .ansi(false)
+        .write_to(&mut out)
+        .unwrap();
+    String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .map(|line| line.rsplit("> ").next().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn store_recovers_from_poisoning() {
+    let store = LogStore::new();
+
+    // panic while the store is held
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        store.extend((0..2).map(|i| match i {
+            0 => entry("before panic"),
+            _ => panic!("panic while the store is held"),
+        }))
+    }));
+    assert!(result.is_err());
+
+    store.push(entry("after panic"));
+    assert_eq!(messages(&store), ["before panic", "after panic"]);
+
+    // views keep drawing the poisoned store
+    let backend = Backend::init(Some(Vec2::new(80, 10)));
+    let stream = backend.stream();
+    let mut siv = Cursive::new();
+    siv.add_layer(FlexiLoggerView::with_store(store.clone()));
+    let mut runner = siv.into_runner(backend);
+    runner.refresh();
+    let screen = stream.try_iter().last().unwrap();
+    assert_eq!(screen.find_occurences("after panic").len(), 1);
+}
+
+#[test]
+fn push_while_held_is_deferred() {
+    let store = LogStore::new();
+
+    // pushing from the iterator runs while the store is held by this thread
+    store.extend((0..2).map(|i| {
+        store.push(entry(&format!("nested {}", i)));
+        entry(&format!("outer {}", i))
+    }));
+    store.push(entry("last"));
+
+    assert_eq!(
+        messages(&store),
+        ["outer 0", "outer 1", "nested 0", "nested 1", "last"]
+    );
+}
+
+#[test]
+fn logging_while_held_does_not_deadlock() {
+    struct Loud;
+
+    impl std::fmt::Display for Loud {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            log::info!("formatting");
+            write!(f, "loud")
+        }
+    }
+
+    let siv = Cursive::new();
+    flexi_logger::Logger::try_with_str("info")
+        .unwrap()
+        .log_to_writer(cursive_flexi_logger_view::cursive_flexi_logger(&siv))
+        .start()
+        .unwrap();
+
+    // log from a formatting path while the store is held, as during a draw
+    let store = LogStore::global();
+    store.extend(std::iter::once_with(|| {
+        log::info!("value is {}", Loud);
+        entry("held")
+    }));
+
+    assert_eq!(messages(&store), ["held", "formatting", "value is loud"]);
+}
+
+#[test]
+fn ranges_and_clear() {
+    let store = LogStore::new();
+    store.extend((0..5).map(|i| entry(&format!("message {}", i))));
+    let messages = |entries: Vec<LogEntry>| {
+        entries
+            .into_iter()
+            .map(|entry| entry.message)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(store.snapshot().len(), 5);
+    assert_eq!(
+        messages(store.range(1..3).collect()),
+        ["message 1", "message 2"]
+    );
+    assert_eq!(messages(store.range(4..).collect()), ["message 4"]);
+    assert!(store.range(5..).next().is_none());
+    assert_eq!(
+        messages(store.seq_range(..=1).collect()),
+        ["message 0", "message 1"]
+    );
+
+    store.clear();
+    assert!(store.snapshot().is_empty());
+    store.push(entry("after clear"));
+    let seqs = store
+        .seq_range(5..)
+        .map(|entry| entry.seq)
+        .collect::<Vec<_>>();
+    assert_eq!(seqs, [5]);
+}
+
+#[test]
+fn subscribers_see_new_entries() {
+    let store = LogStore::new();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+
+    let reader = store.clone();
+    let warnings = Arc::clone(&seen);
+    let subscription = store.subscribe(LogFilter::new().with_level(Level::Warn), move |entry| {
+        // the store is released while callbacks run
+        let retained = reader.snapshot().len();
+        warnings
+            .lock()
+            .unwrap()
+            .push((entry.seq, entry.message.clone(), retained));
+    });
+
+    let mut warning = entry("warning");
+    warning.level = Level::Warn;
+    store.push(entry("info"));
+    store.extend(vec![warning.clone(), entry("info")]);
+    drop(subscription);
+    store.push(warning);
+
+    assert_eq!(*seen.lock().unwrap(), [(1, "warning".to_string(), 3)]);
+}
+
+#[test]
+fn panics_while_held_are_dumped_after_unwinding() {
+    let store = LogStore::new();
+    let crash_file =
+        std::env::temp_dir().join(format!("cursive-flexi-crash-{}.log", std::process::id()));
+    let _ = std::fs::remove_file(&crash_file);
+    PanicHook::new()
+        .store(store.clone())
+        .crash_file(&crash_file)
+        .install();
+
+    // panic while the store is held, as in the draw of a view
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        store.extend((0..2).map(|i| match i {
+            0 => entry("before panic"),
+            _ => panic!("boom"),
+        }))
+    }));
+    let _ = panic::take_hook();
+    assert!(result.is_err());
+
+    let dump = std::fs::read_to_string(&crash_file).unwrap();
+    let _ = std::fs::remove_file(&crash_file);
+    assert!(dump.contains("before panic"), "{}", dump);
+    assert!(dump.contains("panicked: boom"), "{}", dump);
+}
+
+/// Log `message` through `writer`.
+fn write(writer: &dyn LogWriter, message: &str) {
+    writer
+        .write(
+            &mut DeferredNow::new(),
+            &log::Record::builder()
+                .args(format_args!("{}", message))
+                .level(Level::Info)
+                .build(),
+        )
+        .unwrap();
+}
+
+#[test]
+fn non_blocking_writers_count_drops_and_flush_the_queue() {
+    let siv = Cursive::new();
+    let store = LogStore::new();
+    let writer = cursive_flexi_logger_view::cursive_flexi_logger(&siv)
+        .store(store.clone())
+        .non_blocking(1)
+        .unwrap();
+
+    // keep the ingestion thread busy with the first record
+    let (entered, wait_entered) = mpsc::channel();
+    let (release, wait_release) = mpsc::channel::<()>();
+    let wait_release = Mutex::new(wait_release);
+    let _subscription = store.subscribe(LogFilter::new(), move |entry| {
+        if entry.message == "first" {
+            entered.send(()).unwrap();
+            let _ = wait_release.lock().unwrap().recv();
+        }
+    });
+    write(writer.as_ref(), "first");
+    wait_entered.recv().unwrap();
+
+    // the queue holds a single record
+    write(writer.as_ref(), "queued");
+    write(writer.as_ref(), "dropped");
+    write(writer.as_ref(), "dropped");
+    assert_eq!(store.dropped_records(), 2);
+
+    drop(release);
+    writer.flush().unwrap();
+    assert_eq!(messages(&store), ["first", "queued"]);
+}
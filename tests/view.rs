@@ -0,0 +1,130 @@
+use cursive::backends::puppet::Backend;
+use cursive::event::{Event, Key};
+use cursive::{Cursive, Vec2, View};
+use cursive_flexi_logger_view::{
+    show_flexi_logger_debug_console, FlexiLoggerView, Indentable, LogEntry, LogStore, LogViewKeymap,
+};
+use flexi_logger::Level;
+use time::OffsetDateTime;
+
+fn entry(message: &str) -> LogEntry {
+    LogEntry {
+        seq: 0,
+        time: OffsetDateTime::UNIX_EPOCH,
+        level: Level::Info,
+        thread: "test".to_string(),
+        target: "view".to_string(),
+        file: None,
+        line: None,
+        message: message.to_string(),
+        source: None,
+        fields: Vec::new(),
+        json_start: None,
+    }
+}
+
+/// Draw `view` on a puppet screen and return the rows of text.
+fn draw<V: View>(view: V) -> Vec<String> {
+    draw_after(view, Vec::new())
+}
+
+/// Draw `view` on a puppet screen after sending it `events`, and return the
+/// rows of text.
+fn draw_after<V: View>(view: V, events: Vec<Event>) -> Vec<String> {
+    let backend = Backend::init(Some(Vec2::new(80, 10)));
+    let stream = backend.stream();
+    let mut siv = Cursive::new();
+    siv.add_fullscreen_layer(view);
+    let mut runner = siv.into_runner(backend);
+    runner.refresh();
+    for event in events {
+        runner.on_event(event);
+        runner.refresh();
+    }
+    let screen = stream.try_iter().last().unwrap();
+    (0..10)
+        .map(|y| {
+            (0..80)
+                .filter_map(|x| screen[Vec2::new(x, y)].as_ref())
+                .filter_map(|cell| cell.letter.as_option().cloned())
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        })
+        .collect()
+}
+
+#[test]
+fn fields_with_newlines_span_rows() {
+    let store = LogStore::new();
+    let mut first = entry("first");
+    first.fields.push(("trace".to_string(), "a\nb".to_string()));
+    store.push(first);
+    store.push(entry("second"));
+
+    let rows = draw(FlexiLoggerView::with_store(store).no_indent());
+    assert!(rows[0].ends_with("first trace=a"), "{:?}", rows);
+    assert_eq!(rows[1], "b");
+    assert!(rows[2].ends_with("second"), "{:?}", rows);
+}
+
+#[test]
+fn json_detected_by_the_store_is_colored() {
+    let store = LogStore::new();
+    store.push(entry(r#"reply {"ok":true,"ids":[1,2]}"#));
+
+    assert_eq!(store.snapshot()[0].json_start, Some(6));
+    let rows = draw(FlexiLoggerView::with_store(store));
+    assert!(
+        rows[0].ends_with(r#"reply {"ok": true, "ids": [1, 2]}"#),
+        "{:?}",
+        rows
+    );
+    assert_eq!(rows[1], "");
+}
+
+#[test]
+fn scrollable_views_perform_scroll_actions() {
+    let store = LogStore::new();
+    for i in 0..30 {
+        store.push(entry(&format!("record {:02}", i)));
+    }
+    let view = || {
+        FlexiLoggerView::with_store(store.clone())
+            .with_bookmarks()
+            .with_keymap(LogViewKeymap::vim())
+            .into_scrollable()
+    };
+
+    let rows = draw(view());
+    assert!(rows[9].contains("record 29"), "{:?}", rows);
+    let rows = draw_after(view(), vec![Event::Char('g')]);
+    assert!(rows[0].contains("record 00"), "{:?}", rows);
+    let rows = draw_after(view(), vec![Event::Char('g'), Event::CtrlChar('d')]);
+    assert!(rows[0].contains("record 09"), "{:?}", rows);
+    let rows = draw_after(
+        view(),
+        vec![Event::Char('g'), Event::CtrlChar('e'), Event::Char('G')],
+    );
+    assert!(rows[9].contains("record 29"), "{:?}", rows);
+}
+
+#[test]
+fn legacy_console_does_not_clear_records() {
+    assert_eq!(LogViewKeymap::new().action(&Event::CtrlChar('l')), None);
+
+    let store = LogStore::global();
+    store.push(entry("kept"));
+    let mut siv = Cursive::new();
+    show_flexi_logger_debug_console(&mut siv);
+    let mut runner = siv.into_runner(Backend::init(Some(Vec2::new(80, 10))));
+    runner.refresh();
+    for event in [
+        Event::CtrlChar('l'),
+        Event::Char('m'),
+        Event::Key(Key::Enter),
+    ] {
+        runner.on_event(event);
+    }
+    assert!(store.snapshot().iter().any(|entry| entry.message == "kept"));
+}